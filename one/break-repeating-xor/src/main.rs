@@ -1,7 +1,9 @@
-use base64::Base64;
-use clap::{Arg, Command};
-use std::fs::read_to_string;
-use xor::{Xor, XorResult};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Arg, Command,
+};
+use encoding::Encoding;
+use xor::{BreakOptions, English, LanguageModel, Scorer, Xor, XorResult};
 
 const MAX_KEY_LEN: usize = 40;
 
@@ -12,7 +14,7 @@ fn main() -> XorResult<()> {
         .author("Federico Fusco")
         .about("Bruteforces a multiple-byte XOR ciphertext")
         .arg(
-            Arg::new("encode")
+            Arg::new("ciphertext")
                 .short('C')
                 .long("ciphertext")
                 .help("The ciphertext that should be bruteforced"),
@@ -28,7 +30,9 @@ fn main() -> XorResult<()> {
                 .short('E')
                 .long("encoding")
                 .require_equals(true)
-                .value_parser(["hex", "base64"])
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
                 .help("The ciphertext encoding"),
         )
         .arg(
@@ -36,64 +40,65 @@ fn main() -> XorResult<()> {
                 .short('O')
                 .long("output")
                 .require_equals(true)
-                .value_parser(["hex", "base64", "utf"])
-                .help("The ciphertext encoding"),
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
+                .help("The output format which should be returned"),
+        )
+        .arg(
+            Arg::new("model")
+                .short('M')
+                .long("model")
+                .help("A language model to score plaintexts with (defaults to English)"),
         )
         .get_matches();
 
     let encrypted_data = matches.get_one::<String>("ciphertext");
     let filepath = matches.get_one::<String>("path");
     let encoding = matches
-        .get_one::<String>("encoding")
+        .get_one::<Encoding>("encoding")
         .expect("The --encoding option is required!");
     let output_format = matches
-        .get_one::<String>("output")
+        .get_one::<Encoding>("output")
         .expect("The --output option is required!");
 
-    let mut ciphertext = vec![];
-    if let Some(encrypted_data) = encrypted_data {
-        if encoding == "hex" {
-            ciphertext = hex::decode(encrypted_data.as_bytes())?;
-        } else if encoding == "base64" {
-            ciphertext = Base64::decode(encrypted_data.as_bytes().to_vec())
-                .expect("Failed to decode Base64!");
-        }
+    let ciphertext = if let Some(encrypted_data) = encrypted_data {
+        encoding
+            .decode(encrypted_data.as_bytes())
+            .expect("Failed to decode the ciphertext!")
     } else if let Some(filepath) = filepath {
-        let encrypted_data = read_to_string(filepath)?;
-        if encoding == "hex" {
-            ciphertext = hex::decode(encrypted_data.as_bytes())?;
-        } else if encoding == "base64" {
-            ciphertext = Base64::decode(encrypted_data.as_bytes().to_vec())
-                .expect("Failed to decode Base64!");
-        }
+        encoding
+            .decode_file(filepath)
+            .expect("Failed to decode the ciphertext!")
     } else {
         panic!("No ciphertext was provided!")
-    }
+    };
 
-    // Decrypts the data
-    let probable_length = Xor::estimate_key_length(&ciphertext, MAX_KEY_LEN)?;
-    let blocks = Xor::block_ciphertext(&ciphertext, probable_length);
-    let mut blocks = Xor::transpose_blocks(&blocks, probable_length);
-    let blocks = Xor::bruteforce_blocks(&mut blocks);
+    // Loads the language model (if one was given)
+    let scorer: Box<dyn Scorer> = match matches.get_one::<String>("model") {
+        Some(path) => Box::new(LanguageModel::load(path)?),
+        None => Box::new(English),
+    };
 
-    // Reorders bytes into a single vector
-    let mut output: Vec<u8> = vec![];
-    for x in 0..ciphertext.len() {
-        let y = x % probable_length;
-        let z = x / probable_length; // ???
-        output.push(blocks[y][z]);
-    }
+    // Decrypts the data
+    let opts = BreakOptions {
+        max_key_length: MAX_KEY_LEN,
+        ..Default::default()
+    };
+    let result = Xor::break_repeating_key_with(&ciphertext, &opts, scorer.as_ref())?;
 
-    if output_format == "hex" {
-        println!("{}", String::from_utf8(hex::encode(output).into())?);
-    } else if output_format == "base64" {
-        println!(
-            "{}",
-            String::from_utf8(Base64::encode(output).expect("Failed to encode base64!"))?
-        );
-    } else if output_format == "utf" {
-        println!("{}", String::from_utf8_lossy(&output[..]));
-    }
+    eprintln!(
+        "Key ({} bytes, {:.0}% certainty): {:?}",
+        result.key_length,
+        result.score * 100.0,
+        String::from_utf8_lossy(&result.key)
+    );
+    println!(
+        "{}",
+        output_format
+            .encode_to_string(&result.plaintext)
+            .expect("Failed to encode output!")
+    );
 
     Ok(())
 }
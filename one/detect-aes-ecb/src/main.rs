@@ -0,0 +1,84 @@
+use attacks::{AttackResult, Ecb};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    value_parser, Arg, Command,
+};
+use encoding::Encoding;
+use std::fs;
+
+/// The AES block size (in bytes)
+const BLOCK_SIZE: usize = 16;
+
+fn main() -> AttackResult<()> {
+    // Sets CLI options
+    let matches = Command::new("Challenge Eight - Detecting AES in ECB Mode")
+        .version("0.1.0")
+        .author("Federico Fusco")
+        .about("Ranks a list of ciphertexts by how likely they are to be ECB encrypted")
+        .arg(
+            Arg::new("path")
+                .short('P')
+                .long("path")
+                .required(true)
+                .help("The path to the file containing the ciphertexts"),
+        )
+        .arg(
+            Arg::new("encoding")
+                .short('E')
+                .long("encoding")
+                .require_equals(true)
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
+                .help("The ciphertext encoding"),
+        )
+        .arg(
+            Arg::new("top")
+                .short('N')
+                .long("top")
+                .require_equals(true)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("The number of most probable lines which should be displayed"),
+        )
+        .get_matches();
+
+    let path = matches
+        .get_one::<String>("path")
+        .expect("The --path option is required!");
+    let encoding = matches
+        .get_one::<Encoding>("encoding")
+        .expect("The --encoding option is required!");
+    let top = *matches
+        .get_one::<usize>("top")
+        .expect("The --top option is required!");
+
+    // Decodes every line
+    let lines = fs::read_to_string(path).expect("Failed to read the ciphertexts!");
+    let ciphertexts: Vec<Vec<u8>> = lines
+        .lines()
+        .map(|x| {
+            encoding
+                .decode(x.as_bytes())
+                .expect("Failed to decode the ciphertext!")
+        })
+        .collect();
+
+    // Ranks the ciphertexts
+    let ranking = Ecb::rank_ciphertexts(&ciphertexts, BLOCK_SIZE)?;
+
+    // Displays the most probable lines
+    for score in ranking.iter().take(top) {
+        println!(
+            "Line {} ({} repeated blocks, {:.2} bits of block entropy): {}",
+            score.index + 1,
+            score.repeated_blocks,
+            score.entropy,
+            Encoding::Hex
+                .encode_to_string(&ciphertexts[score.index])
+                .expect("Failed to encode output!")
+        );
+    }
+
+    Ok(())
+}
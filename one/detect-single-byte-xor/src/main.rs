@@ -1,9 +1,10 @@
-use clap::{Arg, ArgAction, Command};
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    value_parser, Arg, ArgAction, Command,
 };
-use xor::{Xor, XorResult};
+use encoding::Encoding;
+use std::{fs::File, io::BufReader};
+use xor::{English, LanguageModel, Scorer, Xor, XorResult};
 
 fn main() -> XorResult<()> {
     // Sets CLI options
@@ -30,9 +31,26 @@ fn main() -> XorResult<()> {
                 .short('O')
                 .long("output")
                 .require_equals(true)
-                .value_parser(["hex", "utf"])
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
                 .help("The output format which should be returned"),
         )
+        .arg(
+            Arg::new("model")
+                .short('M')
+                .long("model")
+                .help("A language model to score plaintexts with (defaults to English)"),
+        )
+        .arg(
+            Arg::new("top")
+                .short('N')
+                .long("top")
+                .require_equals(true)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("The number of most probable lines which should be displayed"),
+        )
         .get_matches();
 
     let path = matches
@@ -40,27 +58,35 @@ fn main() -> XorResult<()> {
         .expect("The --path option is required!");
     let hex = matches.get_flag("hex");
     let output = matches
-        .get_one::<String>("output")
+        .get_one::<Encoding>("output")
         .expect("The --output option is required!");
+    let top = *matches
+        .get_one::<usize>("top")
+        .expect("The --top option is required!");
+
+    // Loads the language model (if one was given)
+    let scorer: Box<dyn Scorer + Sync> = match matches.get_one::<String>("model") {
+        Some(path) => Box::new(LanguageModel::load(path)?),
+        None => Box::new(English),
+    };
 
     // Attempts to open the given path
     let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let ciphertexts = reader.lines();
 
-    // Bruteforces the ciphertext
-    let (probability, plaintext) = Xor::single_byte_bruteforce_list(ciphertexts, hex)?;
+    // Bruteforces the ciphertexts
+    let detections = Xor::detect_single_byte_xor(reader, hex, top, scorer.as_ref())?;
 
-    // Displays the plaintext
-    if output == "hex" {
-        println!(
-            "Output ({probability}% certainty): {:?}",
-            hex::encode(plaintext)
-        );
-    } else if output == "utf" {
+    // Displays the plaintexts
+    for detection in detections {
         println!(
-            "Output ({probability}% certainty): {:?}",
-            String::from_utf8_lossy(&plaintext[..])
+            "Output (line {}, key {:#04x}, {:.0}% certainty): {:?}",
+            detection.line,
+            detection.key,
+            detection.score * 100.0,
+            output
+                .encode_to_string(&detection.plaintext)
+                .expect("Failed to encode output!")
         );
     }
 
@@ -1,5 +1,8 @@
-use base64::Base64;
-use clap::{Arg, Command};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Arg, Command,
+};
+use encoding::Encoding;
 use xor::{Xor, XorResult};
 
 fn main() -> XorResult<()> {
@@ -27,7 +30,9 @@ fn main() -> XorResult<()> {
                 .short('E')
                 .long("encoding")
                 .require_equals(true)
-                .value_parser(["hex", "base64"])
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
                 .help("The input value encodings"),
         )
         .arg(
@@ -35,7 +40,9 @@ fn main() -> XorResult<()> {
                 .short('O')
                 .long("output")
                 .require_equals(true)
-                .value_parser(["hex", "base64", "utf"])
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
                 .help("The output format which should be returned"),
         )
         .get_matches();
@@ -47,34 +54,29 @@ fn main() -> XorResult<()> {
         .get_one::<String>("rvalue")
         .expect("The --rvalue option is required!");
     let encoding = matches
-        .get_one::<String>("encoding")
+        .get_one::<Encoding>("encoding")
         .expect("The --encoding option is required!");
     let output = matches
-        .get_one::<String>("output")
+        .get_one::<Encoding>("output")
         .expect("The --output option is required!");
 
     // XORs the values
-    let mut xor: Vec<u8> = vec![];
-    if encoding == "hex" {
-        xor = Xor::vec(&hex::decode(lvalue)?, &hex::decode(rvalue)?)?;
-    } else if encoding == "base64" {
-        xor = Xor::vec(
-            &Base64::decode(lvalue.as_bytes().to_vec()).expect("Failed to decode lvalue!"),
-            &Base64::decode(rvalue.as_bytes().to_vec()).expect("Failed to decode rvalue!"),
-        )?;
-    }
+    let xor = Xor::vec(
+        &encoding
+            .decode(lvalue.as_bytes())
+            .expect("Failed to decode lvalue!"),
+        &encoding
+            .decode(rvalue.as_bytes())
+            .expect("Failed to decode rvalue!"),
+    )?;
 
     // Prints the output
-    if output == "hex" {
-        println!("{}", String::from_utf8(hex::encode(xor).into())?);
-    } else if output == "base64" {
-        println!(
-            "{}",
-            String::from_utf8(Base64::encode(xor).expect("Failed to encode base64 output!"))?
-        );
-    } else if output == "utf" {
-        println!("{}", String::from_utf8(xor)?);
-    }
+    println!(
+        "{}",
+        output
+            .encode_to_string(&xor)
+            .expect("Failed to encode output!")
+    );
 
     Ok(())
 }
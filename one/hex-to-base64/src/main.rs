@@ -1,7 +1,7 @@
-use base64::{Base64, Base64Result};
 use clap::{Arg, ArgAction, Command};
+use encoding::{Base64Variant, Encoding, EncodingResult};
 
-fn main() -> Base64Result<()> {
+fn main() -> EncodingResult<()> {
     // Sets CLI options
     let matches = Command::new("Challenge One - Base64")
         .version("0.1.0")
@@ -29,30 +29,27 @@ fn main() -> Base64Result<()> {
         .get_matches();
 
     // Checks if the inputs are hex
-    let hex = matches.get_flag("hex");
+    let input_encoding = if matches.get_flag("hex") {
+        Encoding::Hex
+    } else {
+        Encoding::Raw
+    };
+    let base64 = Encoding::Base64(Base64Variant::Standard);
 
     // Encodes a given value
     if let Some(encode) = matches.get_one::<String>("encode") {
         // Base64 encodes the input
-        let base64: Vec<u8> = if hex {
-            Base64::encode(hex::decode(encode).expect("Failed to convert to hex!"))?
-        } else {
-            Base64::encode(encode.as_bytes().to_vec())?
-        };
+        let input = input_encoding.decode(encode.as_bytes())?;
 
-        println!("Encoded output: {:?}", String::from_utf8(base64)?);
+        println!("Encoded output: {:?}", base64.encode_to_string(&input)?);
     }
 
     // Decodes a given value
     if let Some(decode) = matches.get_one::<String>("decode") {
         // Base64 decodes the input
-        let base64: Vec<u8> = if hex {
-            Base64::decode(hex::decode(decode).expect("Failed to convert from hex!"))?
-        } else {
-            Base64::decode(decode.as_bytes().to_vec())?
-        };
+        let output = base64.decode(&input_encoding.decode(decode.as_bytes())?)?;
 
-        println!("Decoded output: {:?}", String::from_utf8(base64)?);
+        println!("Decoded output: {:?}", String::from_utf8(output)?);
     }
 
     Ok(())
@@ -1,5 +1,6 @@
 use std::array::TryFromSliceError;
 
+use crate::errors::*;
 use crate::galois::GaloisField;
 
 /// The AES SBOX (this will be removed in the future)
@@ -264,7 +265,7 @@ impl AesKeyOps<8, 15> for AesKey<8> {
                     ^ R_CON[x / NK - 1]
                     ^ words[x - NK];
                 continue;
-            } else if (x - (NK / 2)) % NK == 0 {
+            } else if (x - (NK / 2)).is_multiple_of(NK) {
                 words[x] = Self::sub_bytes(words[x - 1]) ^ words[x - NK];
                 continue;
             }
@@ -308,12 +309,63 @@ impl Default for AesKey<8> {
     }
 }
 
+impl<const N: usize> AesKey<N> {
+    /// Returns the key's raw bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|x| x.to_be_bytes()).collect()
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for AesKey<N> {
+    type Error = AesError;
+
+    /// Builds a key from its raw bytes, which must be 4 * N bytes long
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let size = match N {
+            4 => KeySize::AES_128,
+            6 => KeySize::AES_192,
+            8 => KeySize::AES_256,
+            _ => return Err(AesError::InvalidKeyLength),
+        };
+        if value.len() != N * 4 {
+            return Err(AesError::InvalidKeyLength);
+        }
+
+        let mut data = [0u32; N];
+        for (word, bytes) in data.iter_mut().zip(value.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+
+        Ok(Self { size, data })
+    }
+}
+
 /// The AES internal state, this represents a
 /// 128 bit block of data (can be both) plain
 /// and cipher text
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct AesState(pub [u32; 4]);
 
+impl AesState {
+    /// Loads a block of bytes into the state, column by column
+    pub fn from_bytes(block: &[u8; 16]) -> Self {
+        let mut state = Self::default();
+        for (word, bytes) in state.0.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        state
+    }
+
+    /// Returns the state as a block of bytes
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut block = [0u8; 16];
+        for (bytes, word) in block.chunks_exact_mut(4).zip(self.0) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        block
+    }
+}
+
 /// Implements the operations that can be applied to the state
 pub trait AesStateOps<const N: usize> {
     /// Gets a given row from the state
@@ -527,10 +579,15 @@ impl Aes {
     }
 
     /// Loads raw data into the AES 128 bit state
-    pub fn load_state(&mut self, data: AesState) {
+    pub const fn load_state(&mut self, data: AesState) {
         self.state = data;
     }
 
+    /// Returns the internal state
+    pub const fn state(&self) -> AesState {
+        self.state
+    }
+
     /// Encrypts a block with a given key
     pub fn encrypt_block_128(&mut self, key: &AesKey<4>) {
         /// The number of rounds
@@ -686,7 +743,7 @@ mod tests {
         let key = AesKey {
             size: KeySize::AES_192,
             data: [
-                0x8E73B0F7, 0xDA0E6452, 0xc810F32B, 0x809079E5, 0x62F8EAD2, 0x522C6b7B,
+                0x8E73B0F7, 0xDA0E6452, 0xC810F32B, 0x809079E5, 0x62F8EAD2, 0x522C6B7B,
             ],
         };
 
@@ -718,6 +775,32 @@ mod tests {
         assert_eq!(*round_keys.last().unwrap(), result);
     }
 
+    #[test]
+    fn key_from_bytes() {
+        let key = AesKey::<4>::try_from(&b"YELLOW SUBMARINE"[..]).unwrap();
+
+        assert_eq!(key.size, KeySize::AES_128);
+        assert_eq!(key.data[0], 0x59454C4C);
+        assert_eq!(key.to_bytes(), b"YELLOW SUBMARINE");
+        assert_eq!(
+            AesKey::<6>::try_from(&b"YELLOW SUBMARINE"[..]),
+            Err(AesError::InvalidKeyLength)
+        );
+        assert_eq!(
+            AesKey::<5>::try_from(&[0u8; 20][..]),
+            Err(AesError::InvalidKeyLength)
+        );
+    }
+
+    #[test]
+    fn state_128_bytes() {
+        let block = *b"0123456789ABCDEF";
+        let state = AesState::from_bytes(&block);
+
+        assert_eq!(state.0[1], 0x34353637);
+        assert_eq!(state.to_bytes(), block);
+    }
+
     #[test]
     fn state_128_row() {
         let state = AesState([0x01020304, 0x05060708, 0x090A0B0C, 0x0D0E0F00]);
@@ -839,7 +922,7 @@ mod tests {
     fn aes_encrypt_block_128() {
         let key = AesKey::<4> {
             size: KeySize::AES_128,
-            data: [0x2b7E1516, 0x28AED2A6, 0xABF71588, 0x09CF4F3C],
+            data: [0x2B7E1516, 0x28AED2A6, 0xABF71588, 0x09CF4F3C],
         };
 
         let result = AesState([0x3925841D, 0x2DC09FB, 0xDC118597, 0x196A0B32]);
@@ -855,7 +938,7 @@ mod tests {
     fn aes_decrypt_block_128() {
         let key = AesKey::<4> {
             size: KeySize::AES_128,
-            data: [0x2b7E1516, 0x28AED2A6, 0xABF71588, 0x09CF4F3C],
+            data: [0x2B7E1516, 0x28AED2A6, 0xABF71588, 0x09CF4F3C],
         };
 
         let result = AesState([0x3243F6A8, 0x885A308D, 0x313198A2, 0xE0370734]);
@@ -913,7 +996,7 @@ mod tests {
             ],
         };
 
-        let result = AesState([0x8EA2b7CA, 0x516745BF, 0xEAFC4990, 0x4B496089]);
+        let result = AesState([0x8EA2B7CA, 0x516745BF, 0xEAFC4990, 0x4B496089]);
 
         let mut aes = Aes::default();
         aes.load_state(AesState([0x00112233, 0x44556677, 0x8899AABB, 0xCCDDEEFF]));
@@ -935,7 +1018,7 @@ mod tests {
         let result = AesState([0x00112233, 0x44556677, 0x8899AABB, 0xCCDDEEFF]);
 
         let mut aes = Aes::default();
-        aes.load_state(AesState([0x8EA2b7CA, 0x516745BF, 0xEAFC4990, 0x4B496089]));
+        aes.load_state(AesState([0x8EA2B7CA, 0x516745BF, 0xEAFC4990, 0x4B496089]));
         aes.decrypt_block_256(&key);
 
         assert_eq!(aes.state, result);
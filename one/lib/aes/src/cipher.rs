@@ -0,0 +1,126 @@
+use crate::aes::{AesKey, AesKeyOps, AesRoundKey, AesState, AesStateOps};
+
+/// The AES block size (in bytes)
+pub const BLOCK_SIZE: usize = 16;
+
+/// A block cipher encrypting and decrypting one 128 bit block at a time
+pub trait BlockCipher {
+    /// Encrypts a block in place
+    fn encrypt_block(&self, block: &mut [u8; BLOCK_SIZE]);
+    /// Decrypts a block in place
+    fn decrypt_block(&self, block: &mut [u8; BLOCK_SIZE]);
+}
+
+/// An AES cipher for any key size, the round keys are derived
+/// once (rather than for every block, like `Aes` does)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AesCipher {
+    /// The round keys (one more than the number of rounds)
+    round_keys: Vec<AesRoundKey>,
+}
+
+impl AesCipher {
+    /// Derives the round keys of a 128, 192, or 256 bit key
+    pub fn new<const N: usize, const N_ROUND_KEYS: usize>(key: &AesKey<N>) -> Self
+    where
+        AesKey<N>: AesKeyOps<N, N_ROUND_KEYS>,
+    {
+        Self {
+            round_keys: key.derive_round_keys().to_vec(),
+        }
+    }
+
+    /// The number of rounds, which depends on the key size
+    const fn n_rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+}
+
+impl BlockCipher for AesCipher {
+    fn encrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        let n_rounds = self.n_rounds();
+        let mut state = AesState::from_bytes(block);
+
+        state.add_round_key(&self.round_keys[0]);
+        for round_key in &self.round_keys[1..n_rounds] {
+            state.round(round_key);
+        }
+
+        // The final round skips mixing the columns
+        state.sub_bytes();
+        state.shift_rows();
+        state.add_round_key(&self.round_keys[n_rounds]);
+
+        *block = state.to_bytes();
+    }
+
+    fn decrypt_block(&self, block: &mut [u8; BLOCK_SIZE]) {
+        let n_rounds = self.n_rounds();
+        let mut state = AesState::from_bytes(block);
+
+        state.add_round_key(&self.round_keys[n_rounds]);
+        state.inverse_shift_rows();
+        state.inverse_sub_bytes();
+        for round_key in self.round_keys[1..n_rounds].iter().rev() {
+            state.inverse_round(round_key);
+        }
+        state.add_round_key(&self.round_keys[0]);
+
+        *block = state.to_bytes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::Aes;
+    use encoding::Encoding;
+
+    #[test]
+    fn fips_197_vectors() {
+        let plaintext: [u8; BLOCK_SIZE] = Encoding::Hex
+            .decode(b"00112233445566778899aabbccddeeff")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let key = Encoding::Hex
+            .decode(b"000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap();
+
+        let ciphers = [
+            AesCipher::new(&AesKey::<4>::try_from(&key[..16]).unwrap()),
+            AesCipher::new(&AesKey::<6>::try_from(&key[..24]).unwrap()),
+            AesCipher::new(&AesKey::<8>::try_from(&key[..]).unwrap()),
+        ];
+        let ciphertexts = [
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ];
+
+        for (cipher, ciphertext) in ciphers.iter().zip(ciphertexts) {
+            let mut block = plaintext;
+            cipher.encrypt_block(&mut block);
+            assert_eq!(
+                block.to_vec(),
+                Encoding::Hex.decode(ciphertext.as_bytes()).unwrap()
+            );
+
+            cipher.decrypt_block(&mut block);
+            assert_eq!(block, plaintext);
+        }
+    }
+
+    #[test]
+    fn matches_aes() {
+        let key = AesKey::<4>::try_from(&b"YELLOW SUBMARINE"[..]).unwrap();
+        let mut block = *b"an example block";
+
+        let mut aes = Aes::default();
+        aes.load_state(AesState::from_bytes(&block));
+        aes.encrypt_block_128(&key);
+
+        AesCipher::new(&key).encrypt_block(&mut block);
+        assert_eq!(block, aes.state().to_bytes());
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// The errors that can occur while encrypting or decrypting with AES
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AesError {
+    /// The key must be 16, 24 or 32 bytes long (matching the key type)
+    InvalidKeyLength,
+    /// The block size must be between 1 and 255 bytes
+    InvalidBlockSize,
+    /// The input must be made of whole blocks
+    InvalidLength,
+    /// The PKCS#7 padding is malformed
+    InvalidPadding,
+    /// The region lies (partly) outside of the input
+    OutOfBounds,
+}
+
+/// The result type used throughout the crate
+pub type AesResult<T> = Result<T, AesError>;
+
+impl fmt::Display for AesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidKeyLength => "invalid key length",
+            Self::InvalidBlockSize => "invalid block size",
+            Self::InvalidLength => "input is not made of whole blocks",
+            Self::InvalidPadding => "invalid padding",
+            Self::OutOfBounds => "out of bounds",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for AesError {}
@@ -9,5 +9,13 @@
 
 /// A module dedicated to handling AES operations
 pub mod aes;
+/// A module dedicated to block ciphers whose round keys are derived once
+pub mod cipher;
+/// A module containing the crate's errors
+pub mod errors;
 /// A module dedicated to handling arithmetic in a GaloisField(256)
 mod galois;
+/// A module dedicated to the modes of operation
+pub mod modes;
+/// A module dedicated to PKCS#7 padding
+pub mod padding;
@@ -0,0 +1,298 @@
+use crate::cipher::{BlockCipher, BLOCK_SIZE};
+use crate::errors::*;
+use crate::padding::Pkcs7;
+
+/// The modes of operation, encrypting whole messages with any block cipher
+pub trait ModesOfOperation: BlockCipher {
+    /// Encrypts a PKCS#7 padded plaintext in ECB mode,
+    /// every block is encrypted independently
+    fn encrypt_ecb(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut ciphertext =
+            Pkcs7::pad(plaintext, BLOCK_SIZE).expect("AES blocks are a valid padding size!");
+        self.encrypt_ecb_blocks(&mut ciphertext)
+            .expect("The padded plaintext is made of whole blocks!");
+        ciphertext
+    }
+
+    /// Decrypts an ECB ciphertext, stripping its PKCS#7 padding
+    fn decrypt_ecb(&self, ciphertext: &[u8]) -> AesResult<Vec<u8>> {
+        let mut plaintext = ciphertext.to_vec();
+        self.decrypt_ecb_blocks(&mut plaintext)?;
+        Pkcs7::unpad(&plaintext, BLOCK_SIZE)
+    }
+
+    /// Encrypts a PKCS#7 padded plaintext in CBC mode, every plaintext
+    /// block is XORed with the previous ciphertext block (or the IV)
+    fn encrypt_cbc(&self, iv: &[u8; BLOCK_SIZE], plaintext: &[u8]) -> Vec<u8> {
+        let mut ciphertext =
+            Pkcs7::pad(plaintext, BLOCK_SIZE).expect("AES blocks are a valid padding size!");
+        self.encrypt_cbc_blocks(iv, &mut ciphertext)
+            .expect("The padded plaintext is made of whole blocks!");
+        ciphertext
+    }
+
+    /// Decrypts a CBC ciphertext, stripping its PKCS#7 padding
+    fn decrypt_cbc(&self, iv: &[u8; BLOCK_SIZE], ciphertext: &[u8]) -> AesResult<Vec<u8>> {
+        let mut plaintext = ciphertext.to_vec();
+        self.decrypt_cbc_blocks(iv, &mut plaintext)?;
+        Pkcs7::unpad(&plaintext, BLOCK_SIZE)
+    }
+
+    /// Encrypts (or decrypts) an input in CTR mode, XORing it with the encryption
+    /// of a 64 bit little endian nonce followed by a 64 bit little endian counter
+    fn ctr(&self, nonce: u64, input: &[u8]) -> Vec<u8> {
+        let mut output = input.to_vec();
+        self.ctr_in_place(nonce, &mut output);
+        output
+    }
+
+    /// Encrypts (or decrypts) data in place in CTR mode
+    fn ctr_in_place(&self, nonce: u64, data: &mut [u8]) {
+        self.ctr_in_place_at(nonce, 0, data);
+    }
+
+    /// Encrypts (or decrypts) part of a CTR input, starting `offset` bytes into
+    /// the keystream, only the blocks covering the part are encrypted
+    fn ctr_at(&self, nonce: u64, offset: u64, input: &[u8]) -> Vec<u8> {
+        let mut output = input.to_vec();
+        self.ctr_in_place_at(nonce, offset, &mut output);
+        output
+    }
+
+    /// Encrypts (or decrypts) part of a CTR input in place,
+    /// starting `offset` bytes into the keystream
+    fn ctr_in_place_at(&self, nonce: u64, offset: u64, data: &mut [u8]) {
+        let mut position = offset;
+        let mut data = data;
+        while !data.is_empty() {
+            let counter = position / BLOCK_SIZE as u64;
+            let skip = (position % BLOCK_SIZE as u64) as usize;
+
+            let mut keystream = [0u8; BLOCK_SIZE];
+            keystream[..8].copy_from_slice(&nonce.to_le_bytes());
+            keystream[8..].copy_from_slice(&counter.to_le_bytes());
+            self.encrypt_block(&mut keystream);
+
+            let length = data.len().min(BLOCK_SIZE - skip);
+            let (chunk, rest) = data.split_at_mut(length);
+            chunk
+                .iter_mut()
+                .zip(&keystream[skip..])
+                .for_each(|(x, y)| *x ^= y);
+
+            data = rest;
+            position += length as u64;
+        }
+    }
+
+    /// Replaces the plaintext at `offset` of a CTR ciphertext with
+    /// a new one, without decrypting the rest of the ciphertext
+    fn ctr_edit(
+        &self,
+        nonce: u64,
+        ciphertext: &[u8],
+        offset: usize,
+        plaintext: &[u8],
+    ) -> AesResult<Vec<u8>> {
+        let end = offset
+            .checked_add(plaintext.len())
+            .filter(|&x| x <= ciphertext.len())
+            .ok_or(AesError::OutOfBounds)?;
+
+        let mut output = ciphertext.to_vec();
+        output[offset..end].copy_from_slice(plaintext);
+        self.ctr_in_place_at(nonce, offset as u64, &mut output[offset..end]);
+        Ok(output)
+    }
+
+    /// Encrypts whole blocks in place in ECB mode, without padding
+    fn encrypt_ecb_blocks(&self, data: &mut [u8]) -> AesResult<()> {
+        for block in whole_blocks(data)? {
+            self.encrypt_block(block);
+        }
+        Ok(())
+    }
+
+    /// Decrypts whole blocks in place in ECB mode, without padding
+    fn decrypt_ecb_blocks(&self, data: &mut [u8]) -> AesResult<()> {
+        for block in whole_blocks(data)? {
+            self.decrypt_block(block);
+        }
+        Ok(())
+    }
+
+    /// Encrypts whole blocks in place in CBC mode, without padding
+    fn encrypt_cbc_blocks(&self, iv: &[u8; BLOCK_SIZE], data: &mut [u8]) -> AesResult<()> {
+        let mut previous = *iv;
+        for block in whole_blocks(data)? {
+            block.iter_mut().zip(previous).for_each(|(x, y)| *x ^= y);
+            self.encrypt_block(block);
+            previous = *block;
+        }
+        Ok(())
+    }
+
+    /// Decrypts whole blocks in place in CBC mode, without padding
+    fn decrypt_cbc_blocks(&self, iv: &[u8; BLOCK_SIZE], data: &mut [u8]) -> AesResult<()> {
+        let mut previous = *iv;
+        for block in whole_blocks(data)? {
+            let ciphertext = *block;
+            self.decrypt_block(block);
+            block.iter_mut().zip(previous).for_each(|(x, y)| *x ^= y);
+            previous = ciphertext;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BlockCipher + ?Sized> ModesOfOperation for T {}
+
+/// Splits data into blocks, failing if the last one isn't whole
+fn whole_blocks(data: &mut [u8]) -> AesResult<impl Iterator<Item = &mut [u8; BLOCK_SIZE]>> {
+    if !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(AesError::InvalidLength);
+    }
+
+    Ok(data
+        .chunks_exact_mut(BLOCK_SIZE)
+        .map(|x| x.try_into().expect("The chunks are whole blocks!")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::AesKey;
+    use crate::cipher::AesCipher;
+    use encoding::{Base64Variant, Encoding};
+
+    /// The cipher every mode is tested with
+    fn cipher() -> AesCipher {
+        AesCipher::new(&AesKey::<4>::try_from(&b"YELLOW SUBMARINE"[..]).unwrap())
+    }
+
+    #[test]
+    fn ecb() {
+        let cipher = cipher();
+        let plaintext = b"Play that funky music, white boy. Play that funky music";
+
+        let ciphertext = cipher.encrypt_ecb(plaintext);
+        assert_eq!(ciphertext.len(), 64);
+        assert_eq!(cipher.decrypt_ecb(&ciphertext).unwrap(), plaintext);
+
+        // Equal blocks encrypt to equal blocks
+        let ciphertext = cipher.encrypt_ecb(&[b'A'; 32]);
+        assert_eq!(ciphertext[..16], ciphertext[16..32]);
+
+        assert_eq!(
+            cipher.decrypt_ecb(&ciphertext[..20]),
+            Err(AesError::InvalidLength)
+        );
+        assert_eq!(
+            cipher.decrypt_ecb(&ciphertext[..32]),
+            Err(AesError::InvalidPadding)
+        );
+    }
+
+    #[test]
+    fn cbc_sp_800_38a_vectors() {
+        let key = Encoding::Hex
+            .decode(b"2b7e151628aed2a6abf7158809cf4f3c")
+            .unwrap();
+        let cipher = AesCipher::new(&AesKey::<4>::try_from(&key[..]).unwrap());
+        let iv: [u8; BLOCK_SIZE] = Encoding::Hex
+            .decode(b"000102030405060708090a0b0c0d0e0f")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let plaintext = Encoding::Hex
+            .decode(b"6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51")
+            .unwrap();
+
+        let mut data = plaintext.clone();
+        cipher.encrypt_cbc_blocks(&iv, &mut data).unwrap();
+        assert_eq!(
+            data,
+            Encoding::Hex
+                .decode(b"7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2")
+                .unwrap()
+        );
+
+        cipher.decrypt_cbc_blocks(&iv, &mut data).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn cbc() {
+        let cipher = cipher();
+        let plaintext = b"I'm back and I'm ringin' the bell";
+
+        let ciphertext = cipher.encrypt_cbc(&[7; BLOCK_SIZE], plaintext);
+        assert_eq!(ciphertext.len(), 48);
+        assert_eq!(
+            cipher.decrypt_cbc(&[7; BLOCK_SIZE], &ciphertext).unwrap(),
+            plaintext
+        );
+
+        // Equal blocks are chained into different blocks
+        let ciphertext = cipher.encrypt_cbc(&[0; BLOCK_SIZE], &[b'A'; 32]);
+        assert_ne!(ciphertext[..16], ciphertext[16..32]);
+
+        assert_eq!(
+            cipher.decrypt_cbc(&[0; BLOCK_SIZE], &ciphertext[..17]),
+            Err(AesError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn ctr() {
+        let cipher = cipher();
+        let ciphertext = Encoding::Base64(Base64Variant::Standard)
+            .decode(b"L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==")
+            .unwrap();
+
+        let plaintext = cipher.ctr(0, &ciphertext);
+        assert_eq!(
+            plaintext,
+            b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+        assert_eq!(cipher.ctr(0, &plaintext), ciphertext);
+        assert_ne!(cipher.ctr(1, &plaintext), ciphertext);
+    }
+
+    #[test]
+    fn ctr_seek() {
+        let cipher = cipher();
+        let plaintext: Vec<u8> = (0..100u8).collect();
+        let ciphertext = cipher.ctr(9, &plaintext);
+
+        for (start, end) in [(0, 100), (5, 37), (16, 32), (31, 33), (99, 100)] {
+            assert_eq!(
+                cipher.ctr_at(9, start as u64, &ciphertext[start..end]),
+                &plaintext[start..end]
+            );
+        }
+    }
+
+    #[test]
+    fn ctr_edit() {
+        let cipher = cipher();
+        let ciphertext = cipher.ctr(3, b"The quick brown fox jumps over the lazy dog");
+
+        let edited = cipher.ctr_edit(3, &ciphertext, 16, b"cat").unwrap();
+        assert_eq!(
+            cipher.ctr(3, &edited),
+            b"The quick brown cat jumps over the lazy dog"
+        );
+        assert_eq!(edited[..16], ciphertext[..16]);
+        assert_eq!(edited[19..], ciphertext[19..]);
+
+        assert_eq!(
+            cipher.ctr_edit(3, &ciphertext, 42, b"gs"),
+            Err(AesError::OutOfBounds)
+        );
+        assert_eq!(
+            cipher.ctr_edit(3, &ciphertext, usize::MAX, b"gs"),
+            Err(AesError::OutOfBounds)
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::errors::*;
+
+/// PKCS#7 padding, every padding byte is the number of bytes added
+pub struct Pkcs7 {}
+
+impl Pkcs7 {
+    /// Pads an input to a multiple of the block size,
+    /// a whole block is added if it's already aligned
+    pub fn pad(input: &[u8], block_size: usize) -> AesResult<Vec<u8>> {
+        if !(1..=u8::MAX as usize).contains(&block_size) {
+            return Err(AesError::InvalidBlockSize);
+        }
+
+        let length = block_size - input.len() % block_size;
+        let mut output = Vec::with_capacity(input.len() + length);
+        output.extend_from_slice(input);
+        output.resize(input.len() + length, length as u8);
+
+        Ok(output)
+    }
+
+    /// Strips the padding from an input, checking every padding byte
+    pub fn unpad(input: &[u8], block_size: usize) -> AesResult<Vec<u8>> {
+        Ok(input[..input.len() - Self::padding_length(input, block_size)?].to_vec())
+    }
+
+    /// Validates an input's padding, returning how many bytes it takes up
+    pub fn padding_length(input: &[u8], block_size: usize) -> AesResult<usize> {
+        if !(1..=u8::MAX as usize).contains(&block_size) {
+            return Err(AesError::InvalidBlockSize);
+        }
+        if input.is_empty() || !input.len().is_multiple_of(block_size) {
+            return Err(AesError::InvalidLength);
+        }
+
+        let length = input[input.len() - 1] as usize;
+        if length == 0
+            || length > block_size
+            || input[input.len() - length..]
+                .iter()
+                .any(|&x| x as usize != length)
+        {
+            return Err(AesError::InvalidPadding);
+        }
+
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad() {
+        assert_eq!(
+            Pkcs7::pad(b"YELLOW SUBMARINE", 20).unwrap(),
+            b"YELLOW SUBMARINE\x04\x04\x04\x04"
+        );
+        assert_eq!(Pkcs7::pad(b"", 4).unwrap(), [4; 4]);
+        assert_eq!(Pkcs7::pad(b"abcd", 4).unwrap(), b"abcd\x04\x04\x04\x04");
+        assert_eq!(Pkcs7::pad(b"abc", 0), Err(AesError::InvalidBlockSize));
+        assert_eq!(Pkcs7::pad(b"abc", 256), Err(AesError::InvalidBlockSize));
+    }
+
+    #[test]
+    fn unpad() {
+        assert_eq!(
+            Pkcs7::unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16).unwrap(),
+            b"ICE ICE BABY"
+        );
+        assert_eq!(
+            Pkcs7::unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16),
+            Err(AesError::InvalidPadding)
+        );
+        assert_eq!(
+            Pkcs7::unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+            Err(AesError::InvalidPadding)
+        );
+        assert_eq!(
+            Pkcs7::unpad(b"ICE ICE BABY\x00", 13),
+            Err(AesError::InvalidPadding)
+        );
+        assert_eq!(Pkcs7::unpad(b"abc", 16), Err(AesError::InvalidLength));
+    }
+}
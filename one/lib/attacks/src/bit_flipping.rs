@@ -0,0 +1,131 @@
+use crate::errors::*;
+use xor::Xor;
+
+/// Rewrites known plaintext by flipping ciphertext bits, which
+/// unauthenticated CBC and CTR ciphertexts can't detect
+pub struct BitFlipping {}
+
+impl BitFlipping {
+    /// The XOR delta turning the known plaintext into the desired one
+    pub fn delta(known: &[u8], desired: &[u8]) -> AttackResult<Vec<u8>> {
+        Xor::vec(known, desired).map_err(|_| AttackError::InvalidLength)
+    }
+
+    /// Rewrites the plaintext at `offset` of a CBC ciphertext
+    ///
+    /// The delta is applied to the previous ciphertext block (which
+    /// decrypts to garbage), so the region has to fit in a single block,
+    /// and can't be in the first one (unless the IV is prepended)
+    pub fn flip_cbc(
+        ciphertext: &[u8],
+        block_size: usize,
+        offset: usize,
+        known: &[u8],
+        desired: &[u8],
+    ) -> AttackResult<Vec<u8>> {
+        if block_size == 0 {
+            return Err(AttackError::InvalidBlockSize);
+        }
+
+        let delta = Self::delta(known, desired)?;
+        if !delta.is_empty() && offset / block_size != (offset + delta.len() - 1) / block_size {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let start = offset
+            .checked_sub(block_size)
+            .ok_or(AttackError::OutOfBounds)?;
+        Self::apply(ciphertext, start, &delta)
+    }
+
+    /// Rewrites the plaintext at `offset` of a CTR ciphertext (or any other
+    /// keystream ciphertext), where every byte can be flipped independently
+    pub fn flip_ctr(
+        ciphertext: &[u8],
+        offset: usize,
+        known: &[u8],
+        desired: &[u8],
+    ) -> AttackResult<Vec<u8>> {
+        Self::apply(ciphertext, offset, &Self::delta(known, desired)?)
+    }
+
+    /// XORs a delta into a copy of the ciphertext at the given offset
+    fn apply(ciphertext: &[u8], offset: usize, delta: &[u8]) -> AttackResult<Vec<u8>> {
+        let mut output = ciphertext.to_vec();
+        let end = offset
+            .checked_add(delta.len())
+            .ok_or(AttackError::OutOfBounds)?;
+        let region = output
+            .get_mut(offset..end)
+            .ok_or(AttackError::OutOfBounds)?;
+
+        Xor::xor_in_place(region, delta).unwrap();
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::CommentsOracle;
+    use aes::aes::AesKey;
+    use aes::cipher::BLOCK_SIZE;
+
+    /// Harmless user data, as long as the desired plaintext
+    const USERDATA: &[u8] = b"XadminXtrueX";
+
+    /// The plaintext which grants the admin role
+    const DESIRED: &[u8] = b";admin=true;";
+
+    /// The key every service is created with
+    fn key() -> AesKey<4> {
+        AesKey::try_from(&b"bit flipping key"[..]).unwrap()
+    }
+
+    #[test]
+    fn delta() {
+        assert_eq!(BitFlipping::delta(b"ab", b"ba").unwrap(), [3, 3]);
+        assert_eq!(
+            BitFlipping::delta(b"ab", b"b"),
+            Err(AttackError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn flip_cbc() {
+        let oracle = CommentsOracle::cbc(&key(), [0x24; BLOCK_SIZE]);
+        let ciphertext = oracle.encrypt_comments(USERDATA);
+        assert!(!oracle.is_admin(&ciphertext));
+
+        let offset = CommentsOracle::PREFIX_LENGTH;
+        let forged =
+            BitFlipping::flip_cbc(&ciphertext, BLOCK_SIZE, offset, USERDATA, DESIRED).unwrap();
+        assert!(oracle.is_admin(&forged));
+
+        // Regions crossing a block boundary, or in the first block, can't be flipped
+        assert_eq!(
+            BitFlipping::flip_cbc(&ciphertext, BLOCK_SIZE, offset + 8, USERDATA, DESIRED),
+            Err(AttackError::InvalidLength)
+        );
+        assert_eq!(
+            BitFlipping::flip_cbc(&ciphertext, BLOCK_SIZE, 2, USERDATA, DESIRED),
+            Err(AttackError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn flip_ctr() {
+        let oracle = CommentsOracle::ctr(&key(), 0x1122_3344);
+        let ciphertext = oracle.encrypt_comments(USERDATA);
+        assert!(!oracle.is_admin(&ciphertext));
+
+        let offset = CommentsOracle::PREFIX_LENGTH;
+        let forged = BitFlipping::flip_ctr(&ciphertext, offset, USERDATA, DESIRED).unwrap();
+        assert!(oracle.is_admin(&forged));
+
+        assert_eq!(
+            BitFlipping::flip_ctr(&ciphertext, ciphertext.len() - 4, USERDATA, DESIRED),
+            Err(AttackError::OutOfBounds)
+        );
+    }
+}
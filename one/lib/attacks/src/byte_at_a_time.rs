@@ -0,0 +1,310 @@
+use crate::ecb::{BlockMode, Ecb};
+use crate::errors::*;
+use crate::oracle::EncryptionOracle;
+
+/// The largest block size that is searched for
+const MAX_BLOCK_SIZE: usize = 64;
+
+/// How many times (per possible filler length) an input
+/// is sent before giving up on aligning it
+const MAX_ALIGNMENT_ATTEMPTS: usize = 64;
+
+/// The byte used to align inputs (which never appears in the marker)
+const FILLER: u8 = b'F';
+
+/// The byte chosen inputs are padded with
+const PADDING: u8 = b'A';
+
+/// Recovers the secret an ECB oracle appends to its input, one byte at a time
+///
+/// Whatever the oracle prepends (even a prefix whose length changes with every
+/// call) is skipped by sending two identical marker blocks before the input,
+/// so that the input starts right after them on a block boundary
+pub struct ByteAtATime<'a, O: EncryptionOracle + ?Sized> {
+    /// The oracle under attack
+    oracle: &'a mut O,
+    /// The oracle's block size (in bytes)
+    block_size: usize,
+    /// The encrypted marker block
+    marker: Vec<u8>,
+    /// The filler length which last aligned the input
+    filler: usize,
+    /// How many times the oracle has been queried
+    queries: usize,
+}
+
+impl<'a, O: EncryptionOracle + ?Sized> ByteAtATime<'a, O> {
+    /// Prepares an attack, discovering the block size and confirming the oracle uses ECB
+    pub fn new(oracle: &'a mut O) -> AttackResult<Self> {
+        let mut attack = Self {
+            oracle,
+            block_size: 0,
+            marker: vec![],
+            filler: 1,
+            queries: 0,
+        };
+
+        attack.block_size = attack.discover_block_size()?;
+
+        attack.queries += 1;
+        if Ecb::ecb_or_cbc(attack.oracle, attack.block_size)? != BlockMode::Ecb {
+            return Err(AttackError::NotEcb);
+        }
+
+        attack.marker = attack.discover_marker()?;
+        Ok(attack)
+    }
+
+    /// The oracle's block size (in bytes)
+    pub const fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// How many times the oracle has been queried so far
+    pub const fn queries(&self) -> usize {
+        self.queries
+    }
+
+    /// Recovers the secret the oracle appends to its input
+    ///
+    /// Inputs are padded so that every unknown byte ends a block, the block
+    /// is then compared to the encryptions of all 256 possible last bytes
+    /// (which are sent together, as a single input)
+    pub fn recover_suffix(&mut self) -> AttackResult<Vec<u8>> {
+        let block_size = self.block_size;
+        let length = self.suffix_length()?;
+
+        // Encrypts the suffix once for every padding length
+        let targets = (0..block_size)
+            .map(|x| self.encrypt_aligned(&vec![PADDING; x]))
+            .collect::<AttackResult<Vec<Vec<u8>>>>()?;
+
+        let mut known = vec![PADDING; block_size - 1];
+        for x in 0..length {
+            let padding = block_size - 1 - x % block_size;
+            let block = x / block_size * block_size;
+            let target = targets[padding]
+                .get(block..block + block_size)
+                .ok_or(AttackError::ByteNotFound)?;
+
+            // The last block_size - 1 known bytes, followed by every possible byte
+            let window = &known[known.len() - (block_size - 1)..];
+            let dictionary: Vec<u8> = (0..=u8::MAX)
+                .flat_map(|byte| window.iter().copied().chain([byte]))
+                .collect();
+            let ciphertext = self.encrypt_aligned(&dictionary)?;
+
+            let byte = ciphertext
+                .chunks_exact(block_size)
+                .take(256)
+                .position(|x| x == target)
+                .ok_or(AttackError::ByteNotFound)?;
+            known.push(byte as u8);
+        }
+
+        Ok(known.split_off(block_size - 1))
+    }
+
+    /// Queries the oracle, counting the query
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+        self.queries += 1;
+        self.oracle.encrypt(input)
+    }
+
+    /// Finds the block size as the greatest common divisor of the ciphertext
+    /// lengths, which grow by a block whenever the input crosses a boundary
+    fn discover_block_size(&mut self) -> AttackResult<usize> {
+        let mut block_size = 0;
+        for x in 0..=MAX_BLOCK_SIZE * 2 {
+            block_size = gcd(block_size, self.encrypt(&vec![PADDING; x])?.len());
+        }
+
+        if (2..=MAX_BLOCK_SIZE).contains(&block_size) {
+            Ok(block_size)
+        } else {
+            Err(AttackError::BlockSizeNotFound)
+        }
+    }
+
+    /// Builds an input which, once aligned, is made of two
+    /// identical marker blocks, a block of filler and the payload
+    fn marked_input(&self, filler: usize, payload: &[u8]) -> Vec<u8> {
+        let block_size = self.block_size;
+        let marker = (0..block_size).map(|x| !(x as u8));
+
+        let mut input = vec![FILLER; filler];
+        input.extend(marker.clone().chain(marker));
+        input.extend(vec![FILLER; block_size]);
+        input.extend_from_slice(payload);
+        input
+    }
+
+    /// Learns how the marker block encrypts, as the first pair of identical blocks
+    fn discover_marker(&mut self) -> AttackResult<Vec<u8>> {
+        let block_size = self.block_size;
+        for attempt in 0..block_size * MAX_ALIGNMENT_ATTEMPTS {
+            let filler = 1 + attempt % block_size;
+            let ciphertext = self.encrypt(&self.marked_input(filler, &[]))?;
+
+            let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
+            if let Some(pair) = blocks.windows(2).find(|x| x[0] == x[1]) {
+                self.filler = filler;
+                return Ok(pair[0].to_vec());
+            }
+        }
+
+        Err(AttackError::AlignmentNotFound)
+    }
+
+    /// Encrypts a payload which starts on a block boundary,
+    /// returning the ciphertext from the payload onwards
+    ///
+    /// The filler length which worked last is tried first, so
+    /// a fixed length prefix is aligned with a single query
+    fn encrypt_aligned(&mut self, payload: &[u8]) -> AttackResult<Vec<u8>> {
+        let block_size = self.block_size;
+        for attempt in 0..block_size * MAX_ALIGNMENT_ATTEMPTS {
+            let filler = 1 + (self.filler - 1 + attempt) % block_size;
+            let ciphertext = self.encrypt(&self.marked_input(filler, payload))?;
+
+            let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
+            let position = blocks
+                .windows(2)
+                .position(|x| x[0] == self.marker && x[1] == self.marker);
+            if let Some(position) = position {
+                self.filler = filler;
+                return Ok(ciphertext[(position + 3) * block_size..].to_vec());
+            }
+        }
+
+        Err(AttackError::AlignmentNotFound)
+    }
+
+    /// Finds the secret's length, as padding only grows the
+    /// ciphertext once the input and secret fill the last block
+    fn suffix_length(&mut self) -> AttackResult<usize> {
+        let length = self.encrypt_aligned(&[])?.len();
+        for x in 1..=self.block_size {
+            if self.encrypt_aligned(&vec![PADDING; x])?.len() > length {
+                return Ok(length - x);
+            }
+        }
+
+        Err(AttackError::BlockSizeNotFound)
+    }
+}
+
+/// The greatest common divisor of two numbers
+const fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::aes::AesKey;
+    use aes::cipher::AesCipher;
+    use aes::modes::ModesOfOperation;
+
+    /// The secret appended by the oracle
+    const SECRET: &[u8] = b"Rollin' in my 5.0\nWith my rag-top down so my hair can blow";
+
+    /// Encrypts a prefix, the input and the secret under a fixed key
+    struct Oracle {
+        /// The cipher
+        cipher: AesCipher,
+        /// A xorshift state, from which every prefix is drawn
+        state: u32,
+        /// The length of the prefix, or `None` for a new length every call
+        prefix_length: Option<usize>,
+        /// The secret appended to every input
+        secret: &'static [u8],
+    }
+
+    impl Oracle {
+        /// Creates an oracle with the given prefix behaviour
+        fn new(prefix_length: Option<usize>, secret: &'static [u8]) -> Self {
+            Self {
+                cipher: AesCipher::new(&AesKey::<4>::try_from(&b"an unknown key!!"[..]).unwrap()),
+                state: 0x1234_5678,
+                prefix_length,
+                secret,
+            }
+        }
+
+        /// Draws a pseudorandom byte
+        fn next_byte(&mut self) -> u8 {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 17;
+            self.state ^= self.state << 5;
+            self.state as u8
+        }
+    }
+
+    impl EncryptionOracle for Oracle {
+        fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+            let length = self.prefix_length;
+            let length = length.unwrap_or_else(|| self.next_byte() as usize % 40);
+
+            let mut plaintext: Vec<u8> = (0..length).map(|_| self.next_byte()).collect();
+            plaintext.extend_from_slice(input);
+            plaintext.extend_from_slice(self.secret);
+            Ok(self.cipher.encrypt_ecb(&plaintext))
+        }
+    }
+
+    #[test]
+    fn without_prefix() {
+        let mut oracle = Oracle::new(Some(0), SECRET);
+        let mut attack = ByteAtATime::new(&mut oracle).unwrap();
+
+        assert_eq!(attack.block_size(), 16);
+        assert_eq!(attack.recover_suffix().unwrap(), SECRET);
+    }
+
+    #[test]
+    fn fixed_prefix() {
+        let mut oracle = Oracle::new(Some(21), SECRET);
+        let mut attack = ByteAtATime::new(&mut oracle).unwrap();
+
+        assert_eq!(attack.recover_suffix().unwrap(), SECRET);
+    }
+
+    #[test]
+    fn random_prefix() {
+        // Every input takes a few tries to align, so the secret is kept short
+        let mut oracle = Oracle::new(None, &SECRET[..20]);
+        let mut attack = ByteAtATime::new(&mut oracle).unwrap();
+
+        assert_eq!(attack.recover_suffix().unwrap(), &SECRET[..20]);
+    }
+
+    #[test]
+    fn not_ecb() {
+        // A keystream XOR, so there are no blocks at all
+        let mut oracle = |x: &[u8]| x.iter().map(|y| y ^ 0x5A).collect::<Vec<u8>>();
+        assert!(matches!(
+            ByteAtATime::new(&mut oracle),
+            Err(AttackError::BlockSizeNotFound)
+        ));
+
+        // Padded to whole blocks, but XORed with a counter so equal blocks differ
+        let mut counter = 0u8;
+        let mut oracle = |x: &[u8]| {
+            let mut output = x.to_vec();
+            output.resize(x.len().next_multiple_of(8) + 8, 0);
+            for byte in &mut output {
+                counter = counter.wrapping_add(1);
+                *byte ^= counter;
+            }
+            output
+        };
+        assert!(matches!(
+            ByteAtATime::new(&mut oracle),
+            Err(AttackError::NotEcb)
+        ));
+    }
+}
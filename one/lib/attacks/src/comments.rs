@@ -0,0 +1,120 @@
+use crate::errors::*;
+use crate::oracle::EncryptionOracle;
+use aes::aes::AesKey;
+use aes::cipher::{AesCipher, BLOCK_SIZE};
+use aes::modes::ModesOfOperation;
+
+/// What the user's data is inserted after
+const PREFIX: &[u8] = b"comment1=cooking%20MCs;userdata=";
+
+/// What the user's data is followed by
+const SUFFIX: &[u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+
+/// What grants the admin role, once decrypted
+const ADMIN: &[u8] = b";admin=true;";
+
+/// The mode the comments are encrypted with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    /// CBC with a fixed IV
+    Cbc([u8; BLOCK_SIZE]),
+    /// CTR with a fixed nonce
+    Ctr(u64),
+}
+
+/// A vulnerable service encrypting the user's data between two comments
+///
+/// `;` and `=` are quoted out of the user's data, so `;admin=true;` can't
+/// be injected directly, but the ciphertext isn't authenticated
+pub struct CommentsOracle {
+    /// The service's cipher
+    cipher: AesCipher,
+    /// The mode of operation
+    mode: Mode,
+}
+
+impl CommentsOracle {
+    /// How many bytes precede the user's data
+    pub const PREFIX_LENGTH: usize = PREFIX.len();
+
+    /// Creates a service encrypting with CBC
+    pub fn cbc(key: &AesKey<4>, iv: [u8; BLOCK_SIZE]) -> Self {
+        Self {
+            cipher: AesCipher::new(key),
+            mode: Mode::Cbc(iv),
+        }
+    }
+
+    /// Creates a service encrypting with CTR
+    pub fn ctr(key: &AesKey<4>, nonce: u64) -> Self {
+        Self {
+            cipher: AesCipher::new(key),
+            mode: Mode::Ctr(nonce),
+        }
+    }
+
+    /// Quotes out the metacharacters, and encrypts the user's data between the comments
+    pub fn encrypt_comments(&self, userdata: &[u8]) -> Vec<u8> {
+        let mut plaintext = PREFIX.to_vec();
+        for &x in userdata {
+            match x {
+                b';' => plaintext.extend_from_slice(b"%3B"),
+                b'=' => plaintext.extend_from_slice(b"%3D"),
+                _ => plaintext.push(x),
+            }
+        }
+        plaintext.extend_from_slice(SUFFIX);
+
+        match self.mode {
+            Mode::Cbc(iv) => self.cipher.encrypt_cbc(&iv, &plaintext),
+            Mode::Ctr(nonce) => self.cipher.ctr(nonce, &plaintext),
+        }
+    }
+
+    /// Decrypts the comments
+    pub fn decrypt_comments(&self, ciphertext: &[u8]) -> AttackResult<Vec<u8>> {
+        Ok(match self.mode {
+            Mode::Cbc(iv) => self.cipher.decrypt_cbc(&iv, ciphertext)?,
+            Mode::Ctr(nonce) => self.cipher.ctr(nonce, ciphertext),
+        })
+    }
+
+    /// Whether the decrypted comments contain `;admin=true;`
+    pub fn is_admin(&self, ciphertext: &[u8]) -> bool {
+        self.decrypt_comments(ciphertext)
+            .is_ok_and(|x| x.windows(ADMIN.len()).any(|x| x == ADMIN))
+    }
+}
+
+impl EncryptionOracle for CommentsOracle {
+    /// Encrypts the comments, the input being the user's data
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+        Ok(self.encrypt_comments(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The key every service is created with
+    fn key() -> AesKey<4> {
+        AesKey::try_from(&b"comments service"[..]).unwrap()
+    }
+
+    #[test]
+    fn quote_metacharacters() {
+        for oracle in [
+            CommentsOracle::cbc(&key(), [0; BLOCK_SIZE]),
+            CommentsOracle::ctr(&key(), 7),
+        ] {
+            let ciphertext = oracle.encrypt_comments(b";admin=true;");
+            let plaintext = oracle.decrypt_comments(&ciphertext).unwrap();
+
+            assert_eq!(&plaintext[..CommentsOracle::PREFIX_LENGTH], PREFIX);
+            assert!(plaintext
+                .ends_with(b"%3Badmin%3Dtrue%3B;comment2=%20like%20a%20pound%20of%20bacon"));
+            assert!(!oracle.is_admin(&ciphertext));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::errors::*;
+use aes::aes::AesKey;
+use aes::cipher::AesCipher;
+use aes::modes::ModesOfOperation;
+use xor::Xor;
+
+/// A vulnerable service letting anyone edit its CTR ciphertexts
+/// (e.g. random access storage), without revealing the key
+pub struct EditOracle {
+    /// The service's cipher
+    cipher: AesCipher,
+    /// The nonce every ciphertext is encrypted with
+    nonce: u64,
+}
+
+impl EditOracle {
+    /// Creates a service with the given key and nonce
+    pub fn new(key: &AesKey<4>, nonce: u64) -> Self {
+        Self {
+            cipher: AesCipher::new(key),
+            nonce,
+        }
+    }
+
+    /// Encrypts a plaintext
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        self.cipher.ctr(self.nonce, plaintext)
+    }
+
+    /// Replaces the plaintext at `offset` of a ciphertext, re-encrypting
+    /// only the new plaintext (so large ciphertexts are cheap to edit)
+    pub fn edit(
+        &self,
+        ciphertext: &[u8],
+        offset: usize,
+        plaintext: &[u8],
+    ) -> AttackResult<Vec<u8>> {
+        Ok(self
+            .cipher
+            .ctr_edit(self.nonce, ciphertext, offset, plaintext)?)
+    }
+}
+
+/// Decrypts CTR ciphertexts through an edit function
+pub struct CtrEdit {}
+
+impl CtrEdit {
+    /// Recovers a whole plaintext, with a single edit
+    pub fn recover_plaintext<F>(ciphertext: &[u8], edit: F) -> AttackResult<Vec<u8>>
+    where
+        F: FnOnce(&[u8], usize, &[u8]) -> AttackResult<Vec<u8>>,
+    {
+        Self::recover_range(ciphertext, 0, ciphertext.len(), edit)
+    }
+
+    /// Recovers part of a plaintext
+    ///
+    /// Writing zeros over the part encrypts them to the keystream itself,
+    /// which is then XORed with the original ciphertext
+    pub fn recover_range<F>(
+        ciphertext: &[u8],
+        offset: usize,
+        length: usize,
+        edit: F,
+    ) -> AttackResult<Vec<u8>>
+    where
+        F: FnOnce(&[u8], usize, &[u8]) -> AttackResult<Vec<u8>>,
+    {
+        let end = offset
+            .checked_add(length)
+            .filter(|&x| x <= ciphertext.len())
+            .ok_or(AttackError::OutOfBounds)?;
+
+        let edited = edit(ciphertext, offset, &vec![0; length])?;
+        let keystream = edited.get(offset..end).ok_or(AttackError::OutOfBounds)?;
+
+        Ok(Xor::vec(&ciphertext[offset..end], keystream).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plaintext spanning a few hundred blocks
+    fn plaintext() -> Vec<u8> {
+        b"I'm back and I'm ringin' the bell \n".repeat(128)
+    }
+
+    #[test]
+    fn recover_plaintext() {
+        let oracle = EditOracle::new(&AesKey::try_from(&b"random access!!!"[..]).unwrap(), 42);
+        let plaintext = plaintext();
+        let ciphertext = oracle.encrypt(&plaintext);
+
+        let edit = |x: &[u8], y: usize, z: &[u8]| oracle.edit(x, y, z);
+
+        let recovered = CtrEdit::recover_plaintext(&ciphertext, edit).unwrap();
+        assert_eq!(recovered, plaintext);
+
+        let recovered = CtrEdit::recover_range(&ciphertext, 1000, 70, edit).unwrap();
+        assert_eq!(recovered, &plaintext[1000..1070]);
+
+        assert_eq!(
+            CtrEdit::recover_range(&ciphertext, plaintext.len(), 1, edit),
+            Err(AttackError::OutOfBounds)
+        );
+    }
+}
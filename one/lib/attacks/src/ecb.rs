@@ -0,0 +1,267 @@
+use crate::errors::*;
+use crate::oracle::EncryptionOracle;
+use aes::padding::Pkcs7;
+use std::collections::HashMap;
+use xor::Xor;
+
+/// How likely a ciphertext is to have been encrypted with ECB
+#[derive(Clone, Debug, PartialEq)]
+pub struct EcbScore {
+    /// The ciphertext's index in the list
+    pub index: usize,
+    /// How many blocks are a repeat of an earlier block
+    pub repeated_blocks: usize,
+    /// The Shannon entropy (in bits) of the blocks, which
+    /// is highest when every block is different
+    pub entropy: f64,
+}
+
+/// The block cipher modes an oracle can be distinguished between
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlockMode {
+    /// Electronic Code Book, equal plaintext blocks encrypt to equal ciphertext blocks
+    Ecb,
+    /// Cipher Block Chaining
+    Cbc,
+}
+
+/// Detects and attacks ECB encrypted ciphertexts
+pub struct Ecb {}
+
+impl Ecb {
+    /// Divides a ciphertext into its whole blocks
+    fn whole_blocks(ciphertext: &[u8], block_size: usize) -> Vec<Vec<u8>> {
+        Xor::block_ciphertext(ciphertext, block_size)
+            .into_iter()
+            .filter(|x| x.len() == block_size)
+            .collect()
+    }
+
+    /// Counts how many blocks of a ciphertext are a repeat of an earlier block
+    pub fn repeated_blocks(ciphertext: &[u8], block_size: usize) -> AttackResult<usize> {
+        if block_size == 0 {
+            return Err(AttackError::InvalidBlockSize);
+        }
+
+        let blocks = Self::whole_blocks(ciphertext, block_size);
+        let mut unique: Vec<&Vec<u8>> = blocks.iter().collect();
+        unique.sort_unstable();
+        unique.dedup();
+
+        Ok(blocks.len() - unique.len())
+    }
+
+    /// Calculates the Shannon entropy (in bits) of a ciphertext's blocks
+    pub fn block_entropy(ciphertext: &[u8], block_size: usize) -> AttackResult<f64> {
+        if block_size == 0 {
+            return Err(AttackError::InvalidBlockSize);
+        }
+
+        let blocks = Self::whole_blocks(ciphertext, block_size);
+        let mut counts: HashMap<&Vec<u8>, usize> = HashMap::new();
+        for block in &blocks {
+            *counts.entry(block).or_insert(0) += 1;
+        }
+
+        let total = blocks.len() as f64;
+        Ok(counts
+            .values()
+            .map(|&x| {
+                let probability = x as f64 / total;
+                -probability * probability.log2()
+            })
+            .sum())
+    }
+
+    /// Ranks a list of ciphertexts from the most to the least likely to be ECB encrypted
+    ///
+    /// Ciphertexts are sorted by descending repeated block count, then
+    /// by ascending block entropy, and then by their index in the list
+    pub fn rank_ciphertexts<T: AsRef<[u8]>>(
+        ciphertexts: &[T],
+        block_size: usize,
+    ) -> AttackResult<Vec<EcbScore>> {
+        let mut scores = ciphertexts
+            .iter()
+            .enumerate()
+            .map(|(index, ciphertext)| {
+                Ok(EcbScore {
+                    index,
+                    repeated_blocks: Self::repeated_blocks(ciphertext.as_ref(), block_size)?,
+                    entropy: Self::block_entropy(ciphertext.as_ref(), block_size)?,
+                })
+            })
+            .collect::<AttackResult<Vec<EcbScore>>>()?;
+
+        scores.sort_by(|a, b| {
+            b.repeated_blocks
+                .cmp(&a.repeated_blocks)
+                .then(a.entropy.total_cmp(&b.entropy))
+                .then(a.index.cmp(&b.index))
+        });
+
+        Ok(scores)
+    }
+
+    /// Decides whether a black-box encryptor uses ECB or CBC
+    ///
+    /// Three blocks of identical bytes are encrypted, whatever is prepended or
+    /// appended to them, at least two whole blocks line up and are identical under ECB
+    pub fn ecb_or_cbc<O: EncryptionOracle + ?Sized>(
+        oracle: &mut O,
+        block_size: usize,
+    ) -> AttackResult<BlockMode> {
+        if block_size == 0 {
+            return Err(AttackError::InvalidBlockSize);
+        }
+
+        let ciphertext = oracle.encrypt(&vec![b'A'; block_size * 3])?;
+        if Self::repeated_blocks(&ciphertext, block_size)? > 0 {
+            Ok(BlockMode::Ecb)
+        } else {
+            Ok(BlockMode::Cbc)
+        }
+    }
+
+    /// Forges a ciphertext whose plaintext ends with `desired` rather
+    /// than `replaced`, by splicing blocks the oracle encrypted
+    ///
+    /// The oracle must encrypt its input between a fixed prefix and a suffix ending
+    /// with `replaced`, the padded `desired` value is first encrypted on its own
+    /// blocks, and then pasted over `replaced` once it's pushed onto a new block
+    pub fn cut_and_paste<O: EncryptionOracle + ?Sized>(
+        oracle: &mut O,
+        block_size: usize,
+        replaced: &[u8],
+        desired: &[u8],
+    ) -> AttackResult<Vec<u8>> {
+        let padded = Pkcs7::pad(desired, block_size)?;
+
+        // Finds how much filler aligns the input to a block boundary
+        let mut alignment = None;
+        for filler in 0..block_size {
+            let ciphertext = oracle.encrypt(&vec![b'A'; filler + block_size * 2])?;
+            let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
+            if let Some(x) = blocks.windows(2).position(|x| x[0] == x[1]) {
+                alignment = Some((filler, x * block_size));
+                break;
+            }
+        }
+        let (filler, start) = alignment.ok_or(AttackError::AlignmentNotFound)?;
+
+        // Encrypts the padded value on its own blocks
+        let mut input = vec![b'A'; filler];
+        input.extend_from_slice(&padded);
+        let pasted = oracle
+            .encrypt(&input)?
+            .get(start..start + padded.len())
+            .ok_or(AttackError::AlignmentNotFound)?
+            .to_vec();
+
+        // Finds the input length which fills the last block exactly
+        let length = oracle.encrypt(&[])?.len();
+        let mut filled = None;
+        for x in 1..=block_size {
+            if oracle.encrypt(&vec![b'A'; x])?.len() > length {
+                filled = Some(x);
+                break;
+            }
+        }
+        let filled = filled.ok_or(AttackError::BlockSizeNotFound)?;
+
+        // Pushes the replaced value onto its own block, and cuts it off
+        // (it can't be longer than what the oracle encrypts)
+        let extra = replaced.len() % block_size;
+        let cut = (length + extra)
+            .checked_sub(replaced.len())
+            .filter(|x| x.is_multiple_of(block_size))
+            .ok_or(AttackError::InvalidLength)?;
+        let mut forged = oracle.encrypt(&vec![b'A'; filled + extra])?;
+        forged.truncate(cut);
+        forged.extend(pasted);
+
+        Ok(forged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The AES block size (in bytes)
+    const AES_BLOCK_SIZE: usize = 16;
+
+    /// A toy block cipher (rotating and XORing every byte), so that the
+    /// modes can be told apart without depending on a real cipher
+    fn encrypt_block(block: &[u8]) -> Vec<u8> {
+        block
+            .iter()
+            .enumerate()
+            .map(|(x, y)| y.rotate_left(3) ^ (x as u8).wrapping_mul(73))
+            .collect()
+    }
+
+    /// Encrypts an input with the toy block cipher in either mode,
+    /// surrounded by some fixed bytes
+    fn oracle(mode: BlockMode, input: &[u8]) -> Vec<u8> {
+        let mut plaintext = b"prefix".to_vec();
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(b"a longer suffix");
+        plaintext.resize(plaintext.len().next_multiple_of(AES_BLOCK_SIZE), 0);
+
+        let mut previous = vec![0x5A; AES_BLOCK_SIZE];
+        plaintext
+            .chunks(AES_BLOCK_SIZE)
+            .flat_map(|block| {
+                let block = match mode {
+                    BlockMode::Ecb => encrypt_block(block),
+                    BlockMode::Cbc => {
+                        let chained = Xor::vec(block, &previous).unwrap();
+                        encrypt_block(&chained)
+                    }
+                };
+                previous = block.clone();
+                block
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeated_blocks() {
+        let ciphertext = [[1u8; 16], [2; 16], [1; 16], [1; 16]].concat();
+
+        assert_eq!(Ecb::repeated_blocks(&ciphertext, 16).unwrap(), 2);
+        assert_eq!(Ecb::repeated_blocks(&ciphertext[..56], 16).unwrap(), 1);
+        assert_eq!(Ecb::block_entropy(&[7; 64], 16).unwrap(), 0.0);
+        assert_eq!(Ecb::block_entropy(&ciphertext[16..48], 16).unwrap(), 1.0);
+        assert!(matches!(
+            Ecb::repeated_blocks(&ciphertext, 0),
+            Err(AttackError::InvalidBlockSize)
+        ));
+    }
+
+    #[test]
+    fn rank_ciphertexts() {
+        let random: Vec<u8> = (0..64u8).map(|x| x.wrapping_mul(199) ^ 0x33).collect();
+        let ciphertexts = [
+            random.clone(),
+            [&random[..32], &random[..16], &random[48..]].concat(),
+            [&random[..16]; 4].concat(),
+        ];
+
+        let ranking = Ecb::rank_ciphertexts(&ciphertexts, 16).unwrap();
+        let indices: Vec<usize> = ranking.iter().map(|x| x.index).collect();
+        assert_eq!(indices, [2, 1, 0]);
+        assert_eq!(ranking[0].repeated_blocks, 3);
+        assert_eq!(ranking[2].entropy, 2.0);
+    }
+
+    #[test]
+    fn ecb_or_cbc() {
+        for mode in [BlockMode::Ecb, BlockMode::Cbc] {
+            let detected =
+                Ecb::ecb_or_cbc(&mut |x: &[u8]| oracle(mode, x), AES_BLOCK_SIZE).unwrap();
+            assert_eq!(detected, mode);
+        }
+    }
+}
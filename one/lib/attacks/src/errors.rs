@@ -0,0 +1,87 @@
+use aes::errors::AesError;
+use std::{fmt, io};
+
+/// The errors that can occur while attacking a cipher
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttackError {
+    /// The block size has to be at least one byte
+    InvalidBlockSize,
+    /// The oracle's ciphertext lengths don't reveal a block size
+    BlockSizeNotFound,
+    /// The oracle doesn't encrypt in ECB mode
+    NotEcb,
+    /// The chosen input could never be aligned to a block boundary
+    AlignmentNotFound,
+    /// No byte value reproduces the ciphertext block (or yields valid padding)
+    ByteNotFound,
+    /// The input must be made of whole blocks
+    InvalidLength,
+    /// The recovered plaintext isn't padded correctly
+    InvalidPadding,
+    /// The key has the wrong length
+    InvalidKeyLength,
+    /// A structured cookie is malformed (or isn't UTF-8)
+    MalformedCookie,
+    /// The region lies (partly) outside of the ciphertext
+    OutOfBounds,
+    /// The oracle didn't leak the plaintext it was expected to
+    NothingLeaked,
+    /// An IO error occurred while talking to an oracle
+    IO(io::ErrorKind),
+    /// An oracle sent back a malformed or unsuccessful HTTP message
+    Http,
+    /// The outputs weren't all generated by the same MT19937
+    NotMt19937,
+    /// Outputs can only be truncated to between 1 and 32 bits
+    InvalidTruncation,
+    /// No seed in the window reproduces the output
+    SeedNotFound,
+}
+
+/// The result type used throughout the crate
+pub type AttackResult<T> = Result<T, AttackError>;
+
+impl From<io::Error> for AttackError {
+    fn from(value: io::Error) -> Self {
+        Self::IO(value.kind())
+    }
+}
+
+impl From<AesError> for AttackError {
+    fn from(value: AesError) -> Self {
+        match value {
+            AesError::InvalidKeyLength => Self::InvalidKeyLength,
+            AesError::InvalidBlockSize => Self::InvalidBlockSize,
+            AesError::InvalidLength => Self::InvalidLength,
+            AesError::InvalidPadding => Self::InvalidPadding,
+            AesError::OutOfBounds => Self::OutOfBounds,
+        }
+    }
+}
+
+impl fmt::Display for AttackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidBlockSize => "invalid block size",
+            Self::BlockSizeNotFound => "block size not found",
+            Self::NotEcb => "the oracle doesn't use ECB",
+            Self::AlignmentNotFound => "the input could not be block aligned",
+            Self::ByteNotFound => "no byte matches the ciphertext",
+            Self::InvalidLength => "input is not made of whole blocks",
+            Self::InvalidPadding => "invalid padding",
+            Self::InvalidKeyLength => "invalid key length",
+            Self::MalformedCookie => "malformed cookie",
+            Self::OutOfBounds => "out of bounds",
+            Self::NothingLeaked => "the oracle leaked nothing",
+            Self::IO(kind) => return write!(f, "IO error: {kind}"),
+            Self::Http => "malformed HTTP message",
+            Self::NotMt19937 => "the outputs don't come from a single MT19937",
+            Self::InvalidTruncation => "invalid output truncation",
+            Self::SeedNotFound => "seed not found",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for AttackError {}
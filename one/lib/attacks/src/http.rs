@@ -0,0 +1,247 @@
+use crate::errors::*;
+use crate::oracle::EncryptionOracle;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// The largest body a message may have, anything longer is rejected
+/// before it is read
+const MAX_BODY_LENGTH: usize = 1 << 20;
+
+/// The most a message's start line and headers may take up together,
+/// so that an endless header can't be trickled in
+const MAX_HEAD_LENGTH: u64 = 8 << 10;
+
+/// How long a peer may stall while sending or receiving a message
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An oracle behind an HTTP endpoint, the input is POSTed
+/// as the request's body and the response's body is the ciphertext
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpOracle {
+    /// The server's address (e.g. `127.0.0.1:8080`)
+    address: String,
+    /// The endpoint's path (e.g. `/encrypt`)
+    path: String,
+}
+
+impl HttpOracle {
+    /// Points an oracle at an endpoint
+    pub fn new(address: &str, path: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    /// Serves an oracle over HTTP (a stand-in for a real service), every
+    /// request's body is encrypted, whatever its method and path
+    ///
+    /// Connections are handled one at a time and closed after every response,
+    /// a peer which stalls for longer than `TIMEOUT` is dropped,
+    /// this only returns if accepting a connection fails
+    pub fn serve<O: EncryptionOracle + ?Sized>(
+        listener: &TcpListener,
+        oracle: &mut O,
+    ) -> AttackResult<()> {
+        loop {
+            let (stream, _) = listener.accept()?;
+
+            // A misbehaving client shouldn't take the server down
+            let _ = Self::respond(stream, oracle);
+        }
+    }
+
+    /// Answers a single request
+    fn respond<O: EncryptionOracle + ?Sized>(
+        mut stream: TcpStream,
+        oracle: &mut O,
+    ) -> AttackResult<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let (_, body) = read_message(&mut BufReader::new(&stream))?;
+        let (status, body) = match oracle.encrypt(&body) {
+            Ok(ciphertext) => ("200 OK", ciphertext),
+            Err(error) => ("500 Internal Server Error", error.to_string().into_bytes()),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )?;
+        stream.write_all(&body)?;
+        Ok(stream.flush()?)
+    }
+}
+
+impl EncryptionOracle for HttpOracle {
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+        let address = self
+            .address
+            .to_socket_addrs()?
+            .next()
+            .ok_or(AttackError::Http)?;
+        let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/octet-stream\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.address,
+            input.len()
+        )?;
+        stream.write_all(input)?;
+        stream.flush()?;
+
+        let (start, body) = read_message(&mut BufReader::new(&stream))?;
+        match start.split_whitespace().nth(1) {
+            Some("200") => Ok(body),
+            _ => Err(AttackError::Http),
+        }
+    }
+}
+
+/// Reads an HTTP message, returning its start line and its body (whose
+/// head can't be longer than `MAX_HEAD_LENGTH`, and which must have a
+/// `Content-Length` of at most `MAX_BODY_LENGTH`, no chunked encoding)
+fn read_message<R: BufRead>(reader: &mut R) -> AttackResult<(String, Vec<u8>)> {
+    // Running out of the limit looks like the message ending early
+    let mut head = reader.by_ref().take(MAX_HEAD_LENGTH);
+
+    let mut start = String::new();
+    head.read_line(&mut start)?;
+    if start.trim().is_empty() {
+        return Err(AttackError::Http);
+    }
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if head.read_line(&mut header)? == 0 {
+            return Err(AttackError::Http);
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| AttackError::Http)?;
+            }
+        }
+    }
+
+    if length > MAX_BODY_LENGTH {
+        return Err(AttackError::Http);
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok((start.trim_end().to_string(), body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ByteAtATime;
+    use aes::aes::AesKey;
+    use aes::cipher::AesCipher;
+    use aes::modes::ModesOfOperation;
+    use std::thread;
+    use std::time::Instant;
+
+    /// Starts a server on a free local port, returning its address
+    fn spawn_server<O: EncryptionOracle + Send + 'static>(mut oracle: O) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || HttpOracle::serve(&listener, &mut oracle));
+        address
+    }
+
+    #[test]
+    fn round_trip() {
+        let address = spawn_server(|x: &[u8]| x.iter().rev().copied().collect::<Vec<u8>>());
+        let mut oracle = HttpOracle::new(&address, "/encrypt");
+
+        assert_eq!(oracle.encrypt(b"abc").unwrap(), b"cba");
+        assert_eq!(oracle.encrypt(b"").unwrap(), b"");
+    }
+
+    /// An oracle which always fails
+    struct Failing;
+
+    impl EncryptionOracle for Failing {
+        fn encrypt(&mut self, _input: &[u8]) -> AttackResult<Vec<u8>> {
+            Err(AttackError::NotEcb)
+        }
+    }
+
+    #[test]
+    fn server_error() {
+        let address = spawn_server(Failing);
+        let mut oracle = HttpOracle::new(&address, "/encrypt");
+
+        assert_eq!(oracle.encrypt(b"abc"), Err(AttackError::Http));
+    }
+
+    #[test]
+    fn oversized_body() {
+        let address = spawn_server(|x: &[u8]| x.to_vec());
+
+        let mut stream = TcpStream::connect(&address).unwrap();
+        write!(
+            stream,
+            "POST /encrypt HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        )
+        .unwrap();
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        assert!(response.is_empty());
+
+        // The server is still up
+        let mut oracle = HttpOracle::new(&address, "/encrypt");
+        assert_eq!(oracle.encrypt(b"abc").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn oversized_head() {
+        let address = spawn_server(|x: &[u8]| x.to_vec());
+
+        let mut stream = TcpStream::connect(&address).unwrap();
+        stream
+            .write_all(b"POST /encrypt HTTP/1.1\r\nX-Filler: ")
+            .unwrap();
+        let _ = stream.write_all(&vec![b'a'; MAX_HEAD_LENGTH as usize]);
+
+        // The connection is dropped as soon as the limit is hit, not once the peer times out
+        let started = Instant::now();
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        assert!(response.is_empty());
+        assert!(started.elapsed() < TIMEOUT);
+
+        // The server is still up
+        let mut oracle = HttpOracle::new(&address, "/encrypt");
+        assert_eq!(oracle.encrypt(b"abc").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let cipher = AesCipher::new(&AesKey::<4>::try_from(&b"a remote service"[..]).unwrap());
+        let address = spawn_server(move |x: &[u8]| {
+            cipher.encrypt_ecb(&[b"user=guest&data=", x, b"&secret=hunter2"].concat())
+        });
+
+        let mut oracle = HttpOracle::new(&address, "/encrypt");
+        let mut attack = ByteAtATime::new(&mut oracle).unwrap();
+        assert_eq!(attack.recover_suffix().unwrap(), b"&secret=hunter2");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::errors::*;
+use aes::aes::AesKey;
+use aes::cipher::{AesCipher, BLOCK_SIZE};
+use aes::modes::ModesOfOperation;
+use xor::Xor;
+
+/// A vulnerable service using its CBC key as the IV, which reports
+/// (and leaks) any plaintext containing high ASCII bytes
+pub struct KeyIvOracle {
+    /// The service's cipher
+    cipher: AesCipher,
+    /// The key, which is also the IV
+    iv: [u8; BLOCK_SIZE],
+}
+
+impl KeyIvOracle {
+    /// Creates a service with the given key
+    pub fn new(key: &AesKey<4>) -> Self {
+        Self {
+            cipher: AesCipher::new(key),
+            iv: key.to_bytes().try_into().unwrap(),
+        }
+    }
+
+    /// Encrypts a message, with the key as the IV
+    pub fn encrypt_message(&self, plaintext: &[u8]) -> Vec<u8> {
+        self.cipher.encrypt_cbc(&self.iv, plaintext)
+    }
+
+    /// Decrypts a message, checking that it's ASCII
+    ///
+    /// Messages with high ASCII bytes are reported
+    /// along with the offending plaintext (`Some`)
+    pub fn receive_message(&self, ciphertext: &[u8]) -> AttackResult<Option<Vec<u8>>> {
+        let plaintext = self.cipher.decrypt_cbc(&self.iv, ciphertext)?;
+        if plaintext.is_ascii() {
+            Ok(None)
+        } else {
+            Ok(Some(plaintext))
+        }
+    }
+}
+
+/// Recovers CBC keys which are also used as the IV
+pub struct KeyAsIv {}
+
+impl KeyAsIv {
+    /// Recovers the key from a ciphertext and a service which leaks high ASCII plaintexts
+    ///
+    /// The ciphertext's first block is sent as `C1 || 0 || C1`, followed by the
+    /// last two blocks (so the padding stays valid), the first plaintext block is
+    /// then `D(C1) ^ key` and the third one `D(C1)`, so XORing them gives the key
+    pub fn recover_key<F: FnOnce(&[u8]) -> AttackResult<Option<Vec<u8>>>>(
+        ciphertext: &[u8],
+        leak: F,
+    ) -> AttackResult<AesKey<4>> {
+        if ciphertext.len() < BLOCK_SIZE * 2 || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let first = &ciphertext[..BLOCK_SIZE];
+        let tampered = [
+            first,
+            &[0; BLOCK_SIZE],
+            first,
+            &ciphertext[ciphertext.len() - BLOCK_SIZE * 2..],
+        ]
+        .concat();
+
+        let plaintext = leak(&tampered)?.ok_or(AttackError::NothingLeaked)?;
+        if plaintext.len() < BLOCK_SIZE * 3 {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let key = Xor::vec(
+            &plaintext[..BLOCK_SIZE],
+            &plaintext[BLOCK_SIZE * 2..BLOCK_SIZE * 3],
+        )
+        .unwrap();
+        Ok(AesKey::try_from(&key[..])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recover_key() {
+        let key = AesKey::try_from(&b"the key is an IV"[..]).unwrap();
+        let oracle = KeyIvOracle::new(&key);
+
+        let ciphertext = oracle.encrypt_message(
+            b"comment1=cooking%20MCs;userdata=hello;comment2=%20like%20a%20pound%20of%20bacon",
+        );
+        assert_eq!(oracle.receive_message(&ciphertext), Ok(None));
+
+        let recovered = KeyAsIv::recover_key(&ciphertext, |x| oracle.receive_message(x)).unwrap();
+        assert_eq!(recovered, key);
+    }
+
+    #[test]
+    fn nothing_leaked() {
+        assert_eq!(
+            KeyAsIv::recover_key(&[0; BLOCK_SIZE * 2], |_| Ok(None)),
+            Err(AttackError::NothingLeaked)
+        );
+        assert_eq!(
+            KeyAsIv::recover_key(&[0; BLOCK_SIZE], |_| Ok(None)),
+            Err(AttackError::InvalidLength)
+        );
+    }
+}
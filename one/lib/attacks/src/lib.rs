@@ -0,0 +1,49 @@
+#![warn(
+    clippy::all,
+    clippy::nursery,
+    clippy::missing_docs_in_private_items,
+    missing_docs
+)]
+
+//! A crate of attacks against block cipher modes of operation and pseudorandom generators
+
+/// A module dedicated to flipping CBC and CTR ciphertext bits to rewrite known plaintext
+mod bit_flipping;
+/// A module dedicated to decrypting an ECB oracle's secret suffix a byte at a time
+mod byte_at_a_time;
+/// A module dedicated to the service encrypting the user's data between two comments
+mod comments;
+/// A module dedicated to decrypting CTR ciphertexts which anyone can edit
+mod ctr_edit;
+/// A module dedicated to detecting and attacking ECB
+mod ecb;
+/// A module containing the crate's errors
+mod errors;
+/// A module dedicated to oracles behind an HTTP endpoint
+mod http;
+/// A module dedicated to recovering CBC keys which are also used as the IV
+mod key_iv;
+/// A module dedicated to the MT19937 stream cipher and the tokens generated with it
+mod mt_cipher;
+/// A module dedicated to the oracles attacks are run against
+mod oracle;
+/// A module dedicated to decrypting and forging CBC ciphertexts through a padding oracle
+mod padding_oracle;
+/// A module dedicated to structured cookies and the ECB profile service handing them out
+mod profile;
+/// A module dedicated to cloning MT19937 generators and recovering their seeds
+mod twister;
+
+pub use bit_flipping::BitFlipping;
+pub use byte_at_a_time::ByteAtATime;
+pub use comments::CommentsOracle;
+pub use ctr_edit::{CtrEdit, EditOracle};
+pub use ecb::{BlockMode, Ecb, EcbScore};
+pub use errors::{AttackError, AttackResult};
+pub use http::HttpOracle;
+pub use key_iv::{KeyAsIv, KeyIvOracle};
+pub use mt_cipher::MtCipher;
+pub use oracle::EncryptionOracle;
+pub use padding_oracle::{PaddingDecryption, PaddingForgery, PaddingOracle};
+pub use profile::{Cookie, ProfileOracle};
+pub use twister::Twister;
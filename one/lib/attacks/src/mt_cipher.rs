@@ -0,0 +1,75 @@
+use mt19937::Mt19937;
+use xor::XorIterator;
+
+/// A toy stream cipher, XORing with the outputs (in little endian
+/// order) of an MT19937 seeded with a 16 bit key
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MtCipher {
+    /// The generator's seed
+    seed: u16,
+}
+
+impl MtCipher {
+    /// The length of the password reset tokens (in bytes)
+    pub const TOKEN_LENGTH: usize = 16;
+
+    /// Creates a cipher with the given key
+    pub const fn new(seed: u16) -> Self {
+        Self { seed }
+    }
+
+    /// Encrypts (or decrypts) an input
+    pub fn apply(&self, input: &[u8]) -> Vec<u8> {
+        input
+            .iter()
+            .copied()
+            .xor_keystream(Self::keystream(self.seed.into(), 0))
+            .collect()
+    }
+
+    /// The keystream of a generator with any 32 bit seed, from a given offset onwards
+    ///
+    /// The outputs before the offset are skipped without being tempered
+    pub fn keystream(seed: u32, offset: usize) -> impl Iterator<Item = u8> {
+        let mut generator = Mt19937::new(seed);
+        generator.discard((offset / 4) as u64);
+
+        std::iter::repeat_with(move || generator.next_u32())
+            .flat_map(u32::to_le_bytes)
+            .skip(offset % 4)
+    }
+
+    /// Generates a password reset token from a generator seeded with the current
+    /// time (a UNIX timestamp), as a vulnerable service would
+    pub fn reset_token(timestamp: u32) -> Vec<u8> {
+        Self::keystream(timestamp, 0)
+            .take(Self::TOKEN_LENGTH)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let cipher = MtCipher::new(0x1234);
+        let plaintext = b"A stream cipher built on a twister";
+
+        let ciphertext = cipher.apply(plaintext);
+        assert_ne!(&ciphertext[..], plaintext);
+        assert_eq!(cipher.apply(&ciphertext), plaintext);
+
+        // The keystream is the generator's outputs, in little endian order
+        let mut generator = Mt19937::new(0x1234);
+        let first = generator.next_u32().to_le_bytes();
+        assert_eq!(cipher.apply(&[0; 4]), first);
+
+        let keystream: Vec<u8> = MtCipher::keystream(0x1234, 0).take(40).collect();
+        for offset in [1, 4, 7] {
+            let tail: Vec<u8> = MtCipher::keystream(0x1234, offset).take(20).collect();
+            assert_eq!(tail, keystream[offset..offset + 20]);
+        }
+    }
+}
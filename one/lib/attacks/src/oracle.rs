@@ -0,0 +1,14 @@
+use crate::errors::*;
+
+/// A black box which encrypts chosen inputs (usually surrounded by secret data)
+pub trait EncryptionOracle {
+    /// Encrypts an input, returning the ciphertext
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>>;
+}
+
+/// Any closure can be an (infallible) oracle
+impl<F: FnMut(&[u8]) -> Vec<u8>> EncryptionOracle for F {
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+        Ok(self(input))
+    }
+}
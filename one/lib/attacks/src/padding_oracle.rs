@@ -0,0 +1,264 @@
+use crate::errors::*;
+use aes::padding::Pkcs7;
+use xor::Xor;
+
+/// A CBC ciphertext decrypted through a padding oracle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaddingDecryption {
+    /// The recovered plaintext (without its padding)
+    pub plaintext: Vec<u8>,
+    /// The block cipher's decryption of every ciphertext block, before
+    /// it's XORed with the previous block (or the IV)
+    pub intermediate: Vec<u8>,
+    /// How many times the oracle was queried
+    pub queries: usize,
+}
+
+/// A CBC ciphertext forged through a padding oracle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaddingForgery {
+    /// The IV the ciphertext must be decrypted with
+    pub iv: Vec<u8>,
+    /// The forged ciphertext
+    pub ciphertext: Vec<u8>,
+    /// How many times the oracle was queried
+    pub queries: usize,
+}
+
+/// Decrypts (and encrypts) CBC ciphertexts through an oracle which only
+/// tells whether a ciphertext's PKCS#7 padding is valid
+///
+/// The oracle is given the IV followed by the ciphertext
+pub struct PaddingOracle<F: Fn(&[u8]) -> bool> {
+    /// The padding oracle
+    oracle: F,
+    /// The cipher's block size (in bytes)
+    block_size: usize,
+    /// How many times the oracle has been queried
+    queries: usize,
+}
+
+impl<F: Fn(&[u8]) -> bool> PaddingOracle<F> {
+    /// Prepares an attack on an oracle for a cipher with the given block size
+    pub fn new(oracle: F, block_size: usize) -> AttackResult<Self> {
+        if !(1..=u8::MAX as usize).contains(&block_size) {
+            return Err(AttackError::InvalidBlockSize);
+        }
+
+        Ok(Self {
+            oracle,
+            block_size,
+            queries: 0,
+        })
+    }
+
+    /// How many times the oracle has been queried so far
+    pub const fn queries(&self) -> usize {
+        self.queries
+    }
+
+    /// Decrypts a ciphertext, along with the intermediate state of every block
+    pub fn decrypt(&mut self, iv: &[u8], ciphertext: &[u8]) -> AttackResult<PaddingDecryption> {
+        let block_size = self.block_size;
+        if iv.len() != block_size
+            || ciphertext.is_empty()
+            || !ciphertext.len().is_multiple_of(block_size)
+        {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let queries = self.queries;
+        let mut intermediate = Vec::with_capacity(ciphertext.len());
+        let mut plaintext = Vec::with_capacity(ciphertext.len());
+
+        let mut previous = iv;
+        for block in ciphertext.chunks_exact(block_size) {
+            let state = self.decrypt_block(previous, block)?;
+            plaintext.extend(Xor::vec(&state, previous).unwrap());
+            intermediate.extend(state);
+            previous = block;
+        }
+
+        Ok(PaddingDecryption {
+            plaintext: Pkcs7::unpad(&plaintext, block_size)
+                .map_err(|_| AttackError::InvalidPadding)?,
+            intermediate,
+            queries: self.queries - queries,
+        })
+    }
+
+    /// Encrypts any plaintext without knowing the key (CBC-R)
+    ///
+    /// Starting from an arbitrary last block, every block's intermediate state
+    /// is recovered and XORed with the plaintext to choose the block before it,
+    /// the first one becomes the IV
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> AttackResult<PaddingForgery> {
+        let block_size = self.block_size;
+        let padded =
+            Pkcs7::pad(plaintext, block_size).map_err(|_| AttackError::InvalidBlockSize)?;
+
+        let queries = self.queries;
+        let mut blocks = vec![vec![0; block_size]];
+        for block in padded.chunks_exact(block_size).rev() {
+            let next = blocks.last().unwrap();
+            let state = self.decrypt_block(&vec![0; block_size], next)?;
+            blocks.push(Xor::vec(&state, block).unwrap());
+        }
+        blocks.reverse();
+
+        Ok(PaddingForgery {
+            iv: blocks[0].clone(),
+            ciphertext: blocks[1..].concat(),
+            queries: self.queries - queries,
+        })
+    }
+
+    /// Recovers a block's intermediate state, a byte at a time from the end,
+    /// by tampering with the block before it until the padding is valid
+    ///
+    /// A valid last byte usually means it decrypted to `0x01`, but it might
+    /// also complete a longer padding (e.g. the original one, when the real
+    /// previous block is used), so the byte before it is changed to make sure
+    pub fn decrypt_block(&mut self, previous: &[u8], block: &[u8]) -> AttackResult<Vec<u8>> {
+        let block_size = self.block_size;
+        if previous.len() != block_size || block.len() != block_size {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let mut forged = previous.to_vec();
+        let mut state = vec![0; block_size];
+        for position in (0..block_size).rev() {
+            let padding = (block_size - position) as u8;
+            for x in position + 1..block_size {
+                forged[x] = state[x] ^ padding;
+            }
+
+            let mut found = None;
+            for guess in 0..=u8::MAX {
+                forged[position] = guess;
+                if !self.query(&forged, block) {
+                    continue;
+                }
+
+                // Rules out longer paddings
+                if position == block_size - 1 && position > 0 {
+                    forged[position - 1] ^= 0xFF;
+                    let valid = self.query(&forged, block);
+                    forged[position - 1] ^= 0xFF;
+                    if !valid {
+                        continue;
+                    }
+                }
+
+                found = Some(guess);
+                break;
+            }
+
+            state[position] = found.ok_or(AttackError::ByteNotFound)? ^ padding;
+        }
+
+        Ok(state)
+    }
+
+    /// Asks the oracle whether a single block's padding is valid, given the block before it
+    fn query(&mut self, previous: &[u8], block: &[u8]) -> bool {
+        self.queries += 1;
+        (self.oracle)(&[previous, block].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::aes::AesKey;
+    use aes::cipher::{AesCipher, BlockCipher, BLOCK_SIZE};
+    use aes::modes::ModesOfOperation;
+
+    /// The cipher behind the oracle
+    fn cipher() -> AesCipher {
+        AesCipher::new(&AesKey::<4>::try_from(&b"a padding oracle"[..]).unwrap())
+    }
+
+    /// Decrypts the IV and ciphertext, only revealing whether the padding is valid
+    fn oracle(cipher: &AesCipher, input: &[u8]) -> bool {
+        let (iv, ciphertext) = input.split_at(BLOCK_SIZE);
+        cipher
+            .decrypt_cbc(iv.try_into().unwrap(), ciphertext)
+            .is_ok()
+    }
+
+    #[test]
+    fn decrypt() {
+        let cipher = cipher();
+        let iv = [0x42; BLOCK_SIZE];
+
+        // Both padded with a whole block and with (ambiguous) longer paddings
+        for plaintext in [
+            &b"000000Now that the party is jumping"[..],
+            b"000001With the bass kicked in and the Vega's are pumpin'",
+            b"Quick to the point, to the point, no faking, ok!",
+        ] {
+            let ciphertext = cipher.encrypt_cbc(&iv, plaintext);
+            let mut attack = PaddingOracle::new(|x: &[u8]| oracle(&cipher, x), BLOCK_SIZE).unwrap();
+
+            let decryption = attack.decrypt(&iv, &ciphertext).unwrap();
+            assert_eq!(decryption.plaintext, plaintext);
+            assert_eq!(decryption.queries, attack.queries());
+
+            let mut intermediate = ciphertext.clone();
+            for block in intermediate.chunks_exact_mut(BLOCK_SIZE) {
+                cipher.decrypt_block(block.try_into().unwrap());
+            }
+            assert_eq!(decryption.intermediate, intermediate);
+        }
+    }
+
+    #[test]
+    fn ambiguous_last_byte() {
+        // A toy cipher whose decryption XORs with a key, so that the tampered block's
+        // second to last byte decrypts to 0x02, and 0x02 0x02 is guessed before 0x01
+        let key = [0x1F, 0x3E, 0x5D, 0x7C, 0x9B, 0xBA, 0x02, 0xDA];
+        let oracle = |x: &[u8]| {
+            let plaintext = Xor::vec(&Xor::vec(&x[8..], &key).unwrap(), &x[..8]).unwrap();
+            Pkcs7::unpad(&plaintext, 8).is_ok()
+        };
+
+        let mut attack = PaddingOracle::new(oracle, 8).unwrap();
+        assert_eq!(attack.decrypt_block(&[0; 8], &[0; 8]).unwrap(), key);
+    }
+
+    #[test]
+    fn encrypt() {
+        let cipher = cipher();
+        let mut attack = PaddingOracle::new(|x: &[u8]| oracle(&cipher, x), BLOCK_SIZE).unwrap();
+
+        let plaintext = b"comment1=cooking%20MCs;userdata=x;admin=true";
+        let forgery = attack.encrypt(plaintext).unwrap();
+        assert_eq!(forgery.ciphertext.len(), 48);
+        assert_eq!(forgery.queries, attack.queries());
+
+        let iv = forgery.iv.as_slice().try_into().unwrap();
+        assert_eq!(
+            cipher.decrypt_cbc(iv, &forgery.ciphertext).unwrap(),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let mut attack = PaddingOracle::new(|_: &[u8]| true, BLOCK_SIZE).unwrap();
+
+        assert_eq!(
+            attack.decrypt(&[0; BLOCK_SIZE], &[0; 20]),
+            Err(AttackError::InvalidLength)
+        );
+        assert_eq!(
+            attack.decrypt(&[0; 8], &[0; BLOCK_SIZE]),
+            Err(AttackError::InvalidLength)
+        );
+        assert!(matches!(
+            PaddingOracle::new(|_: &[u8]| true, 0),
+            Err(AttackError::InvalidBlockSize)
+        ));
+    }
+}
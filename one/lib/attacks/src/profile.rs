@@ -0,0 +1,232 @@
+use crate::errors::*;
+use crate::oracle::EncryptionOracle;
+use aes::aes::AesKey;
+use aes::cipher::AesCipher;
+use aes::modes::ModesOfOperation;
+use std::fmt;
+use std::str::FromStr;
+
+/// The characters which are percent escaped in keys and values
+const METACHARACTERS: [char; 3] = ['%', '&', '='];
+
+/// A structured cookie (e.g. `email=foo@bar.com&uid=10&role=user`), made of
+/// `key=value` pairs joined by `&`, where every metacharacter is percent escaped
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cookie {
+    /// The pairs, in order
+    pairs: Vec<(String, String)>,
+}
+
+impl Cookie {
+    /// Creates an empty cookie
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a pair
+    pub fn push(&mut self, key: &str, value: &str) {
+        self.pairs.push((key.to_string(), value.to_string()));
+    }
+
+    /// Gets the value of the first pair with a given key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(x, _)| x == key)
+            .map(|(_, x)| x.as_str())
+    }
+
+    /// The pairs, in order
+    pub fn pairs(&self) -> &[(String, String)] {
+        &self.pairs
+    }
+}
+
+impl FromStr for Cookie {
+    type Err = AttackError;
+
+    /// Parses a cookie, every pair must have exactly one (unescaped) `=`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::new());
+        }
+
+        let pairs = s
+            .split('&')
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').ok_or(AttackError::MalformedCookie)?;
+                if value.contains('=') {
+                    return Err(AttackError::MalformedCookie);
+                }
+
+                Ok((unescape(key)?, unescape(value)?))
+            })
+            .collect::<AttackResult<Vec<(String, String)>>>()?;
+
+        Ok(Self { pairs })
+    }
+}
+
+impl fmt::Display for Cookie {
+    /// Encodes a cookie, escaping every metacharacter
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (x, (key, value)) in self.pairs.iter().enumerate() {
+            if x > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", escape(key), escape(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Percent escapes every metacharacter
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for x in input.chars() {
+        if METACHARACTERS.contains(&x) {
+            output.push_str(&format!("%{:02X}", x as u8));
+        } else {
+            output.push(x);
+        }
+    }
+    output
+}
+
+/// Decodes every percent escape
+fn unescape(input: &str) -> AttackResult<String> {
+    let input = input.as_bytes();
+    let mut output = Vec::with_capacity(input.len());
+
+    let mut x = 0;
+    while x < input.len() {
+        if input[x] == b'%' {
+            let hex = input
+                .get(x + 1..x + 3)
+                .ok_or(AttackError::MalformedCookie)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| AttackError::MalformedCookie)?;
+            output.push(u8::from_str_radix(hex, 16).map_err(|_| AttackError::MalformedCookie)?);
+            x += 3;
+        } else {
+            output.push(input[x]);
+            x += 1;
+        }
+    }
+
+    String::from_utf8(output).map_err(|_| AttackError::MalformedCookie)
+}
+
+/// A vulnerable service handing out ECB encrypted user profiles
+///
+/// Given an email, it encrypts `email=<email>&uid=10&role=user`, metacharacters
+/// are escaped (so no `&role=admin` can be injected) but the blocks aren't authenticated
+pub struct ProfileOracle {
+    /// The service's cipher
+    cipher: AesCipher,
+}
+
+impl ProfileOracle {
+    /// Creates a service with the given key
+    pub fn new(key: &AesKey<4>) -> Self {
+        Self {
+            cipher: AesCipher::new(key),
+        }
+    }
+
+    /// Builds the profile of a new user
+    pub fn profile_for(email: &str) -> Cookie {
+        let mut cookie = Cookie::new();
+        cookie.push("email", email);
+        cookie.push("uid", "10");
+        cookie.push("role", "user");
+        cookie
+    }
+
+    /// Encrypts the profile of a new user
+    pub fn encrypt_profile(&self, email: &str) -> Vec<u8> {
+        self.cipher
+            .encrypt_ecb(Self::profile_for(email).to_string().as_bytes())
+    }
+
+    /// Decrypts and parses a profile
+    pub fn decrypt_profile(&self, ciphertext: &[u8]) -> AttackResult<Cookie> {
+        let plaintext = self.cipher.decrypt_ecb(ciphertext)?;
+        String::from_utf8(plaintext)
+            .map_err(|_| AttackError::MalformedCookie)?
+            .parse()
+    }
+
+    /// Whether a profile grants the admin role
+    pub fn is_admin(&self, ciphertext: &[u8]) -> bool {
+        self.decrypt_profile(ciphertext)
+            .is_ok_and(|x| x.get("role") == Some("admin"))
+    }
+}
+
+impl EncryptionOracle for ProfileOracle {
+    /// Encrypts the profile of a new user, the input being their email
+    fn encrypt(&mut self, input: &[u8]) -> AttackResult<Vec<u8>> {
+        let email = std::str::from_utf8(input).map_err(|_| AttackError::MalformedCookie)?;
+        Ok(self.encrypt_profile(email))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecb::Ecb;
+    use aes::cipher::BLOCK_SIZE;
+
+    #[test]
+    fn parse_cookie() {
+        let cookie: Cookie = "foo=bar&baz=qux&zap=zazzle".parse().unwrap();
+        assert_eq!(cookie.pairs().len(), 3);
+        assert_eq!(cookie.get("baz"), Some("qux"));
+        assert_eq!(cookie.get("nope"), None);
+        assert_eq!(cookie.to_string(), "foo=bar&baz=qux&zap=zazzle");
+
+        assert_eq!("".parse::<Cookie>().unwrap(), Cookie::new());
+        assert_eq!("a=b&c".parse::<Cookie>(), Err(AttackError::MalformedCookie));
+        assert_eq!("a=b=c".parse::<Cookie>(), Err(AttackError::MalformedCookie));
+        assert_eq!("a=%2".parse::<Cookie>(), Err(AttackError::MalformedCookie));
+        assert_eq!("a=%zz".parse::<Cookie>(), Err(AttackError::MalformedCookie));
+    }
+
+    #[test]
+    fn escape_metacharacters() {
+        let cookie = ProfileOracle::profile_for("foo@bar.com&role=admin%");
+        assert_eq!(
+            cookie.to_string(),
+            "email=foo@bar.com%26role%3Dadmin%25&uid=10&role=user"
+        );
+
+        let parsed: Cookie = cookie.to_string().parse().unwrap();
+        assert_eq!(parsed, cookie);
+        assert_eq!(parsed.get("role"), Some("user"));
+    }
+
+    #[test]
+    fn forge_admin() {
+        let mut oracle = ProfileOracle::new(&AesKey::try_from(&b"profile services"[..]).unwrap());
+
+        let ciphertext = oracle.encrypt_profile("foo@bar.com");
+        assert!(!oracle.is_admin(&ciphertext));
+
+        let forged = Ecb::cut_and_paste(&mut oracle, BLOCK_SIZE, b"user", b"admin").unwrap();
+        let profile = oracle.decrypt_profile(&forged).unwrap();
+        assert_eq!(profile.get("role"), Some("admin"));
+        assert_eq!(profile.get("uid"), Some("10"));
+        assert!(oracle.is_admin(&forged));
+    }
+
+    #[test]
+    fn replaced_too_long() {
+        let mut oracle = ProfileOracle::new(&AesKey::try_from(&b"profile services"[..]).unwrap());
+
+        assert_eq!(
+            Ecb::cut_and_paste(&mut oracle, BLOCK_SIZE, &[b'x'; 100], b"admin"),
+            Err(AttackError::InvalidLength)
+        );
+    }
+}
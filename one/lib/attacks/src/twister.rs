@@ -0,0 +1,224 @@
+use crate::errors::*;
+use crate::mt_cipher::MtCipher;
+use mt19937::{untemper, untemper_64, Mt19937, Mt19937_64};
+use std::ops::RangeInclusive;
+
+/// Clones MT19937 generators from their outputs and recovers the seeds of those
+/// seeded with a timestamp (or a short key)
+pub struct Twister {}
+
+impl Twister {
+    /// Clones a generator from (at least) 624 consecutive outputs, wherever they
+    /// start in its stream, the clone then predicts every output following them
+    ///
+    /// Every output is a tempered state word, so untempering them rebuilds the
+    /// whole state, any extra output is used to check the clone
+    pub fn clone_generator(outputs: &[u32]) -> AttackResult<Mt19937> {
+        let (observed, extra) = outputs
+            .split_at_checked(Mt19937::STATE_SIZE)
+            .ok_or(AttackError::InvalidLength)?;
+
+        let state = observed.iter().map(|&x| untemper(x)).collect::<Vec<u32>>();
+        let mut generator = Mt19937::from_state(state.try_into().unwrap());
+        generator.discard(Mt19937::STATE_SIZE as u64);
+
+        if extra.iter().any(|&x| generator.next_u32() != x) {
+            return Err(AttackError::NotMt19937);
+        }
+
+        Ok(generator)
+    }
+
+    /// Clones a 64 bit generator from (at least) 312 consecutive outputs
+    pub fn clone_generator_64(outputs: &[u64]) -> AttackResult<Mt19937_64> {
+        let (observed, extra) = outputs
+            .split_at_checked(Mt19937_64::STATE_SIZE)
+            .ok_or(AttackError::InvalidLength)?;
+
+        let state = observed
+            .iter()
+            .map(|&x| untemper_64(x))
+            .collect::<Vec<u64>>();
+        let mut generator = Mt19937_64::from_state(state.try_into().unwrap());
+        generator.discard(Mt19937_64::STATE_SIZE as u64);
+
+        if extra.iter().any(|&x| generator.next_u64() != x) {
+            return Err(AttackError::NotMt19937);
+        }
+
+        Ok(generator)
+    }
+
+    /// Recovers the seed (e.g. a UNIX timestamp) of a generator from an output
+    ///
+    /// The output is the generator's `skip`th (counting from zero), truncated to its
+    /// `bits` most significant bits, every seed in the window is tried, the latest first
+    pub fn recover_seed(
+        output: u32,
+        bits: u32,
+        skip: u64,
+        window: RangeInclusive<u32>,
+    ) -> AttackResult<u32> {
+        if !(1..=32).contains(&bits) {
+            return Err(AttackError::InvalidTruncation);
+        }
+
+        window
+            .rev()
+            .find(|&seed| {
+                let mut generator = Mt19937::new(seed);
+                generator.discard(skip);
+                generator.next_u32() >> (32 - bits) == output
+            })
+            .ok_or(AttackError::SeedNotFound)
+    }
+
+    /// Recovers the key of an `MtCipher` ciphertext from its last plaintext bytes
+    /// (e.g. a chosen input appended to an unknown prefix), by trying all 2^16 keys
+    pub fn recover_cipher_seed(ciphertext: &[u8], known_suffix: &[u8]) -> AttackResult<u16> {
+        if known_suffix.is_empty() || known_suffix.len() > ciphertext.len() {
+            return Err(AttackError::InvalidLength);
+        }
+
+        let offset = ciphertext.len() - known_suffix.len();
+        let keystream: Vec<u8> = ciphertext[offset..]
+            .iter()
+            .zip(known_suffix)
+            .map(|(x, y)| x ^ y)
+            .collect();
+
+        (0..=u16::MAX)
+            .find(|&seed| {
+                MtCipher::keystream(seed.into(), offset)
+                    .take(keystream.len())
+                    .eq(keystream.iter().copied())
+            })
+            .ok_or(AttackError::SeedNotFound)
+    }
+
+    /// Recovers the timestamp a password reset token's generator was seeded with,
+    /// every seed in the window is tried, the latest first
+    pub fn recover_token_seed(token: &[u8], window: RangeInclusive<u32>) -> AttackResult<u32> {
+        if token.is_empty() {
+            return Err(AttackError::InvalidLength);
+        }
+
+        window
+            .rev()
+            .find(|&seed| {
+                MtCipher::keystream(seed, 0)
+                    .take(token.len())
+                    .eq(token.iter().copied())
+            })
+            .ok_or(AttackError::SeedNotFound)
+    }
+
+    /// Whether a password reset token came from a generator seeded
+    /// with a timestamp at most `max_age` seconds before `now`
+    pub fn is_time_seeded_token(token: &[u8], now: u32, max_age: u32) -> bool {
+        Self::recover_token_seed(token, now.saturating_sub(max_age)..=now).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_generator() {
+        let mut generator = Mt19937::new(0x00C0_FFEE);
+
+        // The outputs don't have to be aligned with the twists
+        generator.discard(1000);
+        let outputs: Vec<u32> = (0..700).map(|_| generator.next_u32()).collect();
+        let mut clone = Twister::clone_generator(&outputs).unwrap();
+        assert!((0..2000).all(|_| clone.next_u32() == generator.next_u32()));
+
+        let mut tampered = outputs.clone();
+        tampered[650] ^= 1;
+        assert_eq!(
+            Twister::clone_generator(&tampered),
+            Err(AttackError::NotMt19937)
+        );
+        assert_eq!(
+            Twister::clone_generator(&outputs[..623]),
+            Err(AttackError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn clone_generator_64() {
+        let mut generator = Mt19937_64::new(0x00C0_FFEE);
+        generator.discard(100);
+
+        let outputs: Vec<u64> = (0..312).map(|_| generator.next_u64()).collect();
+        let mut clone = Twister::clone_generator_64(&outputs).unwrap();
+        assert!((0..1000).all(|_| clone.next_u64() == generator.next_u64()));
+    }
+
+    #[test]
+    fn recover_seed() {
+        // Seeded with the time a few minutes before the attack
+        let now = 1_700_000_000;
+        let seed = now - 317;
+
+        let mut generator = Mt19937::new(seed);
+        let first = generator.next_u32();
+        assert_eq!(
+            Twister::recover_seed(first, 32, 0, now - 1000..=now),
+            Ok(seed)
+        );
+
+        // Later outputs, truncated to 16 bits
+        generator.discard(2);
+        let fourth = generator.next_u32() >> 16;
+        assert_eq!(
+            Twister::recover_seed(fourth, 16, 3, now - 1000..=now),
+            Ok(seed)
+        );
+
+        assert_eq!(
+            Twister::recover_seed(first, 32, 0, now - 100..=now),
+            Err(AttackError::SeedNotFound)
+        );
+        assert_eq!(
+            Twister::recover_seed(first, 0, 0, now - 100..=now),
+            Err(AttackError::InvalidTruncation)
+        );
+    }
+
+    #[test]
+    fn recover_cipher_seed() {
+        let cipher = MtCipher::new(0xBEEF);
+
+        // An unknown prefix followed by a chosen input
+        let plaintext = [&b"some random prefix"[..], &[b'A'; 14]].concat();
+        let ciphertext = cipher.apply(&plaintext);
+        assert_eq!(
+            Twister::recover_cipher_seed(&ciphertext, &[b'A'; 14]),
+            Ok(0xBEEF)
+        );
+
+        assert_eq!(
+            Twister::recover_cipher_seed(&ciphertext, &[]),
+            Err(AttackError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn time_seeded_token() {
+        let now = 1_700_000_000;
+        let token = MtCipher::reset_token(now - 42);
+
+        assert_eq!(
+            Twister::recover_token_seed(&token, now - 100..=now),
+            Ok(now - 42)
+        );
+        assert!(Twister::is_time_seeded_token(&token, now, 100));
+        assert!(!Twister::is_time_seeded_token(&token, now, 10));
+
+        // A token from a generator seeded with something else entirely
+        let token = MtCipher::reset_token(0xDEAD_BEEF);
+        assert!(!Twister::is_time_seeded_token(&token, now, 1000));
+    }
+}
@@ -0,0 +1,14 @@
+//! Fuzzes `Base64::decode`, run it from `lib/base64` with `cargo +nightly fuzz run decode`
+#![no_main]
+
+use base64::Base64;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Decoding must never panic, no matter the input
+    if let Ok(decoded) = Base64::decode(data.to_vec()) {
+        // Anything that decodes must survive a round trip
+        let encoded = Base64::encode(decoded.clone()).expect("Failed to encode base64!");
+        assert_eq!(Base64::decode(encoded).unwrap(), decoded);
+    }
+});
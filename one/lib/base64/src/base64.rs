@@ -18,12 +18,8 @@ impl Base64 {
             .ok_or(Base64Error::LookupFailed)? as u8)
     }
 
-    // Performs a reverse lookup on the base64 lookup table O(n)
+    /// Performs a reverse lookup on the base64 lookup table O(n)
     fn reverse_lookup(output: u8) -> Base64Result<u8> {
-        if output == b'=' {
-            return Ok(65);
-        }
-
         Ok(BASE64_LOOKUP_TABLE
             .iter()
             .position(|&x| x == output as char)
@@ -38,7 +34,7 @@ impl Base64 {
 
         // Pads the bit vector
         let mut padding_length = 0;
-        if input.len() % 6 != 0 {
+        if !input.len().is_multiple_of(6) {
             padding_length = (6 - input.len() % 6) / 2;
             for _ in 0..6 - input.len() % 6 {
                 input.push(false);
@@ -63,35 +59,40 @@ impl Base64 {
     }
 
     /// Decodes a base64 encoded vector to a vector of bytes
+    ///
+    /// The input must be padded, and padding is only
+    /// accepted at the very end of the input
     pub fn decode(input: Vec<u8>) -> Base64Result<Vec<u8>> {
-        // Strips the padding (if it's present)
-        let mut padding_length: usize = 0;
-        if let Some(padding_pos) = input.iter().position(|&x| x == b'=') {
-            padding_length = (input.len() - padding_pos) * 2;
+        // Checks that the input is made up of quartets
+        if !input.len().is_multiple_of(4) {
+            return Err(Base64Error::InvalidLength);
         }
 
-        // Performs a reverse lookup
-        let input: Vec<u8> = input
-            .into_iter()
-            .map(|x| Self::reverse_lookup(x).unwrap())
-            .collect();
+        // Strips the padding (if it's present)
+        let padding_length = input.iter().rev().take_while(|&&x| x == b'=').count();
+        if padding_length > 2 {
+            return Err(Base64Error::InvalidPadding);
+        }
 
-        // Converts the output vector to a bit vector
-        let input: BitVec<u8, Msb0> = BitVec::from_vec(input);
+        // Performs a reverse lookup, any padding left
+        // in the middle of the input fails here
+        let input: Vec<u8> = input[..input.len() - padding_length]
+            .iter()
+            .map(|&x| Self::reverse_lookup(x))
+            .collect::<Base64Result<Vec<u8>>>()?;
 
-        // Removes extra bits
-        let mut tmp_input: BitVec<u8, Msb0> = BitVec::EMPTY;
-        for x in (0..input.len()).step_by(8) {
-            tmp_input.extend_from_bitslice(&input[x + 2..x + 8]);
+        // Converts the sextets to a bit vector
+        let mut bits: BitVec<u8, Msb0> = BitVec::with_capacity(input.len() * 6);
+        for sextet in input {
+            bits.extend_from_bitslice(&sextet.view_bits::<Msb0>()[2..]);
         }
-        tmp_input = tmp_input[..tmp_input.len() - (padding_length / 2) * 6].to_bitvec();
 
-        // Gets the octets
+        // Gets the octets, the leftover bits were only there to pad the last sextet
         let mut output: Vec<u8> = vec![];
-        for x in (0..tmp_input.len() - padding_length).step_by(8) {
+        for x in (0..bits.len() - bits.len() % 8).step_by(8) {
             let mut byte: u8 = 0;
             for y in x..x + 8 {
-                byte = (byte << 1) | (tmp_input[y] as u8);
+                byte = (byte << 1) | (bits[y] as u8);
             }
             output.push(byte);
         }
@@ -99,3 +100,105 @@ impl Base64 {
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// The test vectors from RFC 4648 §10
+    const RFC_4648_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn encode_rfc_4648_vectors() {
+        for (plaintext, encoded) in RFC_4648_VECTORS {
+            assert_eq!(
+                Base64::encode(plaintext.as_bytes().to_vec()).unwrap(),
+                encoded.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_rfc_4648_vectors() {
+        for (plaintext, encoded) in RFC_4648_VECTORS {
+            assert_eq!(
+                Base64::decode(encoded.as_bytes().to_vec()).unwrap(),
+                plaintext.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_invalid_length() {
+        assert!(matches!(
+            Base64::decode(b"Zm9vY".to_vec()),
+            Err(Base64Error::InvalidLength)
+        ));
+        assert!(matches!(
+            Base64::decode(b"Zg".to_vec()),
+            Err(Base64Error::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn decode_invalid_padding() {
+        assert!(matches!(
+            Base64::decode(b"Z===".to_vec()),
+            Err(Base64Error::InvalidPadding)
+        ));
+        assert!(matches!(
+            Base64::decode(b"====".to_vec()),
+            Err(Base64Error::InvalidPadding)
+        ));
+        assert!(matches!(
+            Base64::decode(b"Zg==Zg==".to_vec()),
+            Err(Base64Error::LookupFailed)
+        ));
+    }
+
+    #[test]
+    fn decode_invalid_character() {
+        // Every input is a valid length, so only the illegal character can fail it
+        for input in [
+            &b"Zm9v\nYmF"[..],
+            b"Zm9-",
+            b"Zm9vYm!y",
+            b"\x00m9v",
+            b"Zm\xff=",
+        ] {
+            assert!(matches!(
+                Base64::decode(input.to_vec()),
+                Err(Base64Error::LookupFailed)
+            ));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(input in proptest::collection::vec(any::<u8>(), 0..=4096)) {
+            let encoded = Base64::encode(input.clone()).unwrap();
+
+            prop_assert_eq!(encoded.len(), input.len().div_ceil(3) * 4);
+            prop_assert_eq!(Base64::decode(encoded).unwrap(), input);
+        }
+
+        #[test]
+        fn decode_never_panics(input in proptest::collection::vec(any::<u8>(), 0..=64)) {
+            let _ = Base64::decode(input);
+        }
+
+        #[test]
+        fn decode_never_panics_on_alphabet(input in "[A-Za-z0-9+/=]{0,64}") {
+            let _ = Base64::decode(input.into_bytes());
+        }
+    }
+}
@@ -4,6 +4,10 @@ use std::{num::TryFromIntError, string::FromUtf8Error};
 pub enum Base64Error {
     /// A lookup in the base64 table failed (invalid index)
     LookupFailed,
+    /// The input isn't made up of whole quartets
+    InvalidLength,
+    /// The input has more than two padding characters
+    InvalidPadding,
     /// Something went wrong while converting from hex
     HexConversion,
     /// Something went wrong while converting from UTF-8
@@ -2,4 +2,4 @@ mod base64;
 mod errors;
 
 pub use base64::Base64;
-pub use errors::Base64Result;
+pub use errors::{Base64Error, Base64Result};
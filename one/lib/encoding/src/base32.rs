@@ -0,0 +1,68 @@
+use crate::errors::{EncodingError, EncodingResult};
+
+/// The RFC 4648 base32 alphabet
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Looks up the value of a base32 character
+const fn reverse_lookup(character: u8) -> EncodingResult<u8> {
+    match character {
+        b'A'..=b'Z' => Ok(character - b'A'),
+        b'2'..=b'7' => Ok(character - b'2' + 26),
+        _ => Err(EncodingError::InvalidCharacter),
+    }
+}
+
+/// Encodes a slice of bytes to padded base32
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut output: Vec<u8> = Vec::with_capacity(input.len().div_ceil(5) * 8);
+
+    for chunk in input.chunks(5) {
+        // Packs the chunk into the top 40 bits of a u64
+        let mut buffer = [0u8; 8];
+        buffer[3..3 + chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_be_bytes(buffer);
+
+        // Every started quintet is emitted, the rest is padding
+        let n_characters = (chunk.len() * 8).div_ceil(5);
+        for x in 0..8 {
+            if x < n_characters {
+                output.push(BASE32_ALPHABET[((bits >> (35 - x * 5)) & 0x1F) as usize]);
+            } else {
+                output.push(b'=');
+            }
+        }
+    }
+
+    output
+}
+
+/// Decodes a (padded or unpadded) base32 input
+pub fn decode(input: &[u8]) -> EncodingResult<Vec<u8>> {
+    // Strips the padding, which is only allowed at the very end
+    let data_length = input.iter().position(|&x| x == b'=').unwrap_or(input.len());
+    if input[data_length..].iter().any(|&x| x != b'=') {
+        return Err(EncodingError::InvalidPadding);
+    }
+    if data_length != input.len() && !input.len().is_multiple_of(8) {
+        return Err(EncodingError::InvalidPadding);
+    }
+
+    // Only these trailing group lengths map onto a whole number of bytes
+    let data = &input[..data_length];
+    if !matches!(data.len() % 8, 0 | 2 | 4 | 5 | 7) {
+        return Err(EncodingError::InvalidLength);
+    }
+
+    let mut output: Vec<u8> = Vec::with_capacity(data.len() * 5 / 8);
+    for chunk in data.chunks(8) {
+        let mut bits: u64 = 0;
+        for (x, &character) in chunk.iter().enumerate() {
+            bits |= u64::from(reverse_lookup(character)?) << (35 - x * 5);
+        }
+
+        let n_bytes = chunk.len() * 5 / 8;
+        output.extend_from_slice(&bits.to_be_bytes()[3..3 + n_bytes]);
+    }
+
+    Ok(output)
+}
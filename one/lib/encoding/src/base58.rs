@@ -0,0 +1,72 @@
+use crate::errors::{EncodingError, EncodingResult};
+
+/// The bitcoin base58 alphabet (no `0`, `O`, `I`, or `l`)
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Looks up the value of a base58 character
+fn reverse_lookup(character: u8) -> EncodingResult<u8> {
+    BASE58_ALPHABET
+        .iter()
+        .position(|&x| x == character)
+        .map(|x| x as u8)
+        .ok_or(EncodingError::InvalidCharacter)
+}
+
+/// Encodes a slice of bytes to base58
+///
+/// Every leading zero byte is represented by a leading `1`
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let n_zeros = input.iter().take_while(|&&x| x == 0).count();
+
+    // Repeatedly divides the big-endian number by 58, keeping
+    // the digits in little-endian order
+    let mut digits: Vec<u8> = vec![];
+    for &byte in &input[n_zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut output = vec![BASE58_ALPHABET[0]; n_zeros];
+    output.extend(digits.iter().rev().map(|&x| BASE58_ALPHABET[x as usize]));
+
+    output
+}
+
+/// Decodes a base58 input
+pub fn decode(input: &[u8]) -> EncodingResult<Vec<u8>> {
+    let n_zeros = input
+        .iter()
+        .take_while(|&&x| x == BASE58_ALPHABET[0])
+        .count();
+
+    // Multiplies the accumulated number by 58, keeping
+    // the bytes in little-endian order
+    let mut bytes: Vec<u8> = vec![];
+    for &character in &input[n_zeros..] {
+        let mut carry = u32::from(reverse_lookup(character)?);
+        for byte in &mut bytes {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut output = vec![0u8; n_zeros];
+    output.extend(bytes.iter().rev());
+
+    Ok(output)
+}
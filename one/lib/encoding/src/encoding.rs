@@ -0,0 +1,361 @@
+use crate::errors::{EncodingError, EncodingResult};
+use crate::{base32, base58, escaped};
+use base64::Base64;
+use std::{fmt, fs, path::Path, str::FromStr};
+
+/// The supported base64 alphabets and padding rules
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Base64Variant {
+    /// The RFC 4648 alphabet (`+` and `/`) with padding
+    #[default]
+    Standard,
+    /// The RFC 4648 alphabet (`+` and `/`) without padding
+    StandardNoPad,
+    /// The URL and filename safe alphabet (`-` and `_`) with padding
+    UrlSafe,
+    /// The URL and filename safe alphabet (`-` and `_`) without padding
+    UrlSafeNoPad,
+}
+
+impl Base64Variant {
+    /// Whether or not the variant uses the URL safe alphabet
+    const fn is_url_safe(self) -> bool {
+        matches!(self, Self::UrlSafe | Self::UrlSafeNoPad)
+    }
+
+    /// Whether or not the variant pads its output
+    const fn is_padded(self) -> bool {
+        matches!(self, Self::Standard | Self::UrlSafe)
+    }
+}
+
+/// A way of representing raw bytes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Lowercase hexadecimal, decoding accepts either case
+    Hex,
+    /// Base64 in one of its variants
+    Base64(Base64Variant),
+    /// RFC 4648 base32
+    Base32,
+    /// Bitcoin flavoured base58
+    Base58,
+    /// The raw bytes themselves
+    #[default]
+    Raw,
+    /// ASCII with every other byte escaped (e.g `\n` or `\xff`)
+    Escaped,
+}
+
+impl Encoding {
+    /// Every name accepted by `Encoding::from_str`, this is
+    /// handy for restricting CLI values
+    pub const NAMES: [&'static str; 10] = [
+        "hex",
+        "base64",
+        "base64-nopad",
+        "base64url",
+        "base64url-nopad",
+        "base32",
+        "base58",
+        "raw",
+        "utf",
+        "escaped",
+    ];
+
+    /// Encodes the given bytes
+    pub fn encode(&self, input: &[u8]) -> EncodingResult<Vec<u8>> {
+        let output = match self {
+            Self::Hex => hex::encode(input).into_bytes(),
+            Self::Base64(variant) => {
+                let mut output = Base64::encode(input.to_vec())?;
+                if variant.is_url_safe() {
+                    for character in &mut output {
+                        match character {
+                            b'+' => *character = b'-',
+                            b'/' => *character = b'_',
+                            _ => {}
+                        }
+                    }
+                }
+                if !variant.is_padded() {
+                    output.retain(|&x| x != b'=');
+                }
+
+                output
+            }
+            Self::Base32 => base32::encode(input),
+            Self::Base58 => base58::encode(input),
+            Self::Raw => input.to_vec(),
+            Self::Escaped => escaped::encode(input),
+        };
+
+        Ok(output)
+    }
+
+    /// Decodes the given input
+    ///
+    /// ASCII whitespace (e.g line breaks) is ignored for
+    /// every encoding except `Raw` and `Escaped`
+    pub fn decode(&self, input: &[u8]) -> EncodingResult<Vec<u8>> {
+        let stripped: Vec<u8> = input
+            .iter()
+            .copied()
+            .filter(|x| !x.is_ascii_whitespace())
+            .collect();
+
+        match self {
+            Self::Hex => Ok(hex::decode(stripped)?),
+            Self::Base64(variant) => Self::decode_base64(&stripped, *variant),
+            Self::Base32 => base32::decode(&stripped),
+            Self::Base58 => base58::decode(&stripped),
+            Self::Raw => Ok(input.to_vec()),
+            Self::Escaped => escaped::decode(input),
+        }
+    }
+
+    /// Encodes the given bytes into a printable string
+    ///
+    /// `Raw` output is converted lossily, since it isn't
+    /// guaranteed to be valid UTF-8
+    pub fn encode_to_string(&self, input: &[u8]) -> EncodingResult<String> {
+        if *self == Self::Raw {
+            return Ok(String::from_utf8_lossy(input).to_string());
+        }
+
+        Ok(String::from_utf8(self.encode(input)?)?)
+    }
+
+    /// Reads and decodes the contents of a file
+    pub fn decode_file<P: AsRef<Path>>(&self, path: P) -> EncodingResult<Vec<u8>> {
+        self.decode(&fs::read(path)?)
+    }
+
+    /// Validates a base64 input and hands it to the base64 crate
+    fn decode_base64(input: &[u8], variant: Base64Variant) -> EncodingResult<Vec<u8>> {
+        // Padding is only allowed at the very end
+        let data_length = input.iter().position(|&x| x == b'=').unwrap_or(input.len());
+        let padding_length = input.len() - data_length;
+        if padding_length > 2 || input[data_length..].iter().any(|&x| x != b'=') {
+            return Err(EncodingError::InvalidPadding);
+        }
+        if padding_length != 0 && !input.len().is_multiple_of(4) {
+            return Err(EncodingError::InvalidPadding);
+        }
+        if data_length % 4 == 1 {
+            return Err(EncodingError::InvalidLength);
+        }
+
+        // Converts the input to the standard alphabet
+        let mut data: Vec<u8> = Vec::with_capacity(data_length + 2);
+        for &character in &input[..data_length] {
+            let character = match (character, variant.is_url_safe()) {
+                (b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9', _) => character,
+                (b'+' | b'/', false) => character,
+                (b'-', true) => b'+',
+                (b'_', true) => b'/',
+                _ => return Err(EncodingError::InvalidCharacter),
+            };
+            data.push(character);
+        }
+
+        // Restores the padding, regardless of whether the input had it
+        while !data.len().is_multiple_of(4) {
+            data.push(b'=');
+        }
+
+        Ok(Base64::decode(data)?)
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64(Base64Variant::Standard)),
+            "base64-nopad" => Ok(Self::Base64(Base64Variant::StandardNoPad)),
+            "base64url" => Ok(Self::Base64(Base64Variant::UrlSafe)),
+            "base64url-nopad" => Ok(Self::Base64(Base64Variant::UrlSafeNoPad)),
+            "base32" => Ok(Self::Base32),
+            "base58" => Ok(Self::Base58),
+            "raw" | "utf" | "utf8" | "binary" => Ok(Self::Raw),
+            "escaped" => Ok(Self::Escaped),
+            _ => Err(EncodingError::UnknownEncoding),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Hex => "hex",
+            Self::Base64(Base64Variant::Standard) => "base64",
+            Self::Base64(Base64Variant::StandardNoPad) => "base64-nopad",
+            Self::Base64(Base64Variant::UrlSafe) => "base64url",
+            Self::Base64(Base64Variant::UrlSafeNoPad) => "base64url-nopad",
+            Self::Base32 => "base32",
+            Self::Base58 => "base58",
+            Self::Raw => "raw",
+            Self::Escaped => "escaped",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every supported encoding
+    const ENCODINGS: [Encoding; 9] = [
+        Encoding::Hex,
+        Encoding::Base64(Base64Variant::Standard),
+        Encoding::Base64(Base64Variant::StandardNoPad),
+        Encoding::Base64(Base64Variant::UrlSafe),
+        Encoding::Base64(Base64Variant::UrlSafeNoPad),
+        Encoding::Base32,
+        Encoding::Base58,
+        Encoding::Raw,
+        Encoding::Escaped,
+    ];
+
+    #[test]
+    fn hex_encode() {
+        assert_eq!(Encoding::Hex.encode(b"\x00\xffK").unwrap(), b"00ff4b");
+        assert_eq!(Encoding::Hex.decode(b"00FF4b").unwrap(), b"\x00\xffK");
+        assert_eq!(
+            Encoding::Hex.decode(b"0").unwrap_err(),
+            EncodingError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn base64_variants() {
+        let input = b"\xfb\xff\xbf?";
+
+        let standard = Encoding::Base64(Base64Variant::Standard);
+        let url_safe_no_pad = Encoding::Base64(Base64Variant::UrlSafeNoPad);
+
+        assert_eq!(standard.encode(input).unwrap(), b"+/+/Pw==");
+        assert_eq!(url_safe_no_pad.encode(input).unwrap(), b"-_-_Pw");
+        assert_eq!(url_safe_no_pad.decode(b"-_-_Pw").unwrap(), input);
+        assert_eq!(url_safe_no_pad.decode(b"-_-_Pw==").unwrap(), input);
+        assert_eq!(
+            standard.decode(b"-_-_Pw==").unwrap_err(),
+            EncodingError::InvalidCharacter
+        );
+    }
+
+    #[test]
+    fn base64_invalid_padding() {
+        let standard = Encoding::Base64(Base64Variant::Standard);
+
+        assert_eq!(
+            standard.decode(b"Zg=a").unwrap_err(),
+            EncodingError::InvalidPadding
+        );
+        assert_eq!(
+            standard.decode(b"Z===").unwrap_err(),
+            EncodingError::InvalidPadding
+        );
+        assert_eq!(
+            standard.decode(b"Zm9vY").unwrap_err(),
+            EncodingError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn base64_ignores_whitespace() {
+        assert_eq!(
+            Encoding::Base64(Base64Variant::Standard)
+                .decode(b"Zm9v\nYmFy\r\n")
+                .unwrap(),
+            b"foobar"
+        );
+    }
+
+    #[test]
+    fn base32_rfc_4648_vectors() {
+        let vectors: [(&[u8], &[u8]); 7] = [
+            (b"", b""),
+            (b"f", b"MY======"),
+            (b"fo", b"MZXQ===="),
+            (b"foo", b"MZXW6==="),
+            (b"foob", b"MZXW6YQ="),
+            (b"fooba", b"MZXW6YTB"),
+            (b"foobar", b"MZXW6YTBOI======"),
+        ];
+
+        for (plaintext, encoded) in vectors {
+            assert_eq!(Encoding::Base32.encode(plaintext).unwrap(), encoded);
+            assert_eq!(Encoding::Base32.decode(encoded).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn base58_vectors() {
+        assert_eq!(
+            Encoding::Base58.encode(b"Hello World!").unwrap(),
+            b"2NEpo7TZRRrLZSi2U"
+        );
+        assert_eq!(
+            Encoding::Base58.decode(b"11233QC4").unwrap(),
+            b"\x00\x00(\x7f\xb4\xcd"
+        );
+        assert_eq!(
+            Encoding::Base58.decode(b"0OIl").unwrap_err(),
+            EncodingError::InvalidCharacter
+        );
+    }
+
+    #[test]
+    fn escaped_encode() {
+        assert_eq!(
+            Encoding::Escaped.encode(b"a\n\"\\\x7f").unwrap(),
+            b"a\\n\\\"\\\\\\x7f"
+        );
+        assert_eq!(
+            Encoding::Escaped.decode(b"\\0\\x41\\t").unwrap(),
+            b"\x00A\t"
+        );
+        assert_eq!(
+            Encoding::Escaped.decode(b"\\x4").unwrap_err(),
+            EncodingError::InvalidEscape
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let input: Vec<u8> = (0..=255).chain([0, 0, 1]).collect();
+
+        for encoding in ENCODINGS {
+            for length in 0..input.len() {
+                let encoded = encoding.encode(&input[..length]).unwrap();
+                assert_eq!(
+                    encoding.decode(&encoded).unwrap(),
+                    &input[..length],
+                    "{encoding} failed with a length of {length}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn names() {
+        for name in Encoding::NAMES {
+            assert!(name.parse::<Encoding>().is_ok());
+        }
+
+        for encoding in ENCODINGS {
+            assert_eq!(encoding.to_string().parse::<Encoding>(), Ok(encoding));
+        }
+
+        assert_eq!(
+            "ebcdic".parse::<Encoding>(),
+            Err(EncodingError::UnknownEncoding)
+        );
+    }
+}
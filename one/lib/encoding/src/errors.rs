@@ -0,0 +1,75 @@
+use base64::Base64Error;
+use hex::FromHexError;
+use std::{fmt, string::FromUtf8Error};
+
+/// The errors that can occur while encoding or decoding data
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The input contained a character outside of the encoding's alphabet
+    InvalidCharacter,
+    /// The length of the input isn't valid for the encoding
+    InvalidLength,
+    /// The input's padding was malformed
+    InvalidPadding,
+    /// The input contained a malformed escape sequence
+    InvalidEscape,
+    /// The given encoding name isn't recognized
+    UnknownEncoding,
+    /// An I/O error occurred
+    IO,
+    /// Something went wrong while converting from UTF-8
+    Utf8Conversion,
+}
+
+/// The result type used throughout the crate
+pub type EncodingResult<T> = Result<T, EncodingError>;
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidCharacter => "invalid character in input",
+            Self::InvalidLength => "invalid input length",
+            Self::InvalidPadding => "invalid padding",
+            Self::InvalidEscape => "invalid escape sequence",
+            Self::UnknownEncoding => "unknown encoding",
+            Self::IO => "an I/O error occurred",
+            Self::Utf8Conversion => "invalid UTF-8",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+impl From<std::io::Error> for EncodingError {
+    fn from(_error: std::io::Error) -> Self {
+        Self::IO
+    }
+}
+
+impl From<FromUtf8Error> for EncodingError {
+    fn from(_error: FromUtf8Error) -> Self {
+        Self::Utf8Conversion
+    }
+}
+
+impl From<FromHexError> for EncodingError {
+    fn from(error: FromHexError) -> Self {
+        match error {
+            FromHexError::InvalidHexCharacter { .. } => Self::InvalidCharacter,
+            FromHexError::OddLength | FromHexError::InvalidStringLength => Self::InvalidLength,
+        }
+    }
+}
+
+impl From<Base64Error> for EncodingError {
+    fn from(error: Base64Error) -> Self {
+        match error {
+            Base64Error::Utf8Conversion => Self::Utf8Conversion,
+            Base64Error::LookupFailed | Base64Error::HexConversion => Self::InvalidCharacter,
+            Base64Error::InvalidLength => Self::InvalidLength,
+            Base64Error::InvalidPadding => Self::InvalidPadding,
+        }
+    }
+}
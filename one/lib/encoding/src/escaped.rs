@@ -0,0 +1,49 @@
+use crate::errors::{EncodingError, EncodingResult};
+
+/// Escapes every non-printable byte, quotes, and backslashes
+/// (e.g `\n`, `\"`, or `\x7f`), leaving the rest untouched
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    input.escape_ascii().collect()
+}
+
+/// Converts an ASCII hex digit to its value
+fn hex_digit(digit: u8) -> EncodingResult<u8> {
+    (digit as char)
+        .to_digit(16)
+        .map(|x| x as u8)
+        .ok_or(EncodingError::InvalidEscape)
+}
+
+/// Unescapes an escaped ASCII input
+///
+/// This accepts every escape produced by `encode` along with `\0`
+pub fn decode(input: &[u8]) -> EncodingResult<Vec<u8>> {
+    let mut output: Vec<u8> = Vec::with_capacity(input.len());
+
+    let mut input = input.iter().copied();
+    while let Some(byte) = input.next() {
+        if byte != b'\\' {
+            output.push(byte);
+            continue;
+        }
+
+        let escaped = match input.next().ok_or(EncodingError::InvalidEscape)? {
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'0' => b'\0',
+            b'\\' => b'\\',
+            b'\'' => b'\'',
+            b'"' => b'"',
+            b'x' => {
+                let high = hex_digit(input.next().ok_or(EncodingError::InvalidEscape)?)?;
+                let low = hex_digit(input.next().ok_or(EncodingError::InvalidEscape)?)?;
+                (high << 4) | low
+            }
+            _ => return Err(EncodingError::InvalidEscape),
+        };
+        output.push(escaped);
+    }
+
+    Ok(output)
+}
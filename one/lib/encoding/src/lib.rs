@@ -0,0 +1,22 @@
+#![warn(
+    clippy::all,
+    clippy::nursery,
+    clippy::missing_docs_in_private_items,
+    missing_docs
+)]
+
+//! A crate for converting bytes to and from their textual encodings
+
+/// A module dedicated to RFC 4648 base32
+mod base32;
+/// A module dedicated to (bitcoin flavoured) base58
+mod base58;
+/// A module dedicated to the `Encoding` enum
+mod encoding;
+/// A module containing the crate's errors
+mod errors;
+/// A module dedicated to escaped ASCII
+mod escaped;
+
+pub use encoding::{Base64Variant, Encoding};
+pub use errors::{EncodingError, EncodingResult};
@@ -0,0 +1,22 @@
+#![warn(
+    clippy::all,
+    clippy::nursery,
+    clippy::missing_docs_in_private_items,
+    missing_docs
+)]
+
+//! A crate implementing the MT19937 Mersenne Twister
+
+/// A module dedicated to the 32 bit MT19937
+mod mt19937;
+/// A module dedicated to the 64 bit MT19937-64
+mod mt19937_64;
+/// A module implementing rand_core's traits
+#[cfg(feature = "rand_core")]
+mod rng;
+/// A module dedicated to tempering outputs (and undoing it)
+mod temper;
+
+pub use mt19937::Mt19937;
+pub use mt19937_64::Mt19937_64;
+pub use temper::{temper, temper_64, untemper, untemper_64};
@@ -0,0 +1,205 @@
+use crate::temper::temper;
+
+/// The number of words in the state
+const N: usize = 624;
+
+/// The offset of the word mixed into every twisted word
+const M: usize = 397;
+
+/// The twist matrix's last row
+const MATRIX_A: u32 = 0x9908_B0DF;
+
+/// The most significant bit
+const UPPER_MASK: u32 = 0x8000_0000;
+
+/// The 31 least significant bits
+const LOWER_MASK: u32 = 0x7FFF_FFFF;
+
+/// The 32 bit MT19937 Mersenne Twister
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mt19937 {
+    /// The state words
+    state: [u32; N],
+    /// The index of the next word to temper (the state is twisted once it reaches `N`)
+    index: usize,
+}
+
+impl Mt19937 {
+    /// The number of words in the state
+    pub const STATE_SIZE: usize = N;
+
+    /// The seed used by the reference implementation when none is given
+    pub const DEFAULT_SEED: u32 = 5489;
+
+    /// Seeds a generator with a 32 bit integer
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0u32; N];
+        state[0] = seed;
+        for x in 1..N {
+            state[x] = 1_812_433_253u32
+                .wrapping_mul(state[x - 1] ^ (state[x - 1] >> 30))
+                .wrapping_add(x as u32);
+        }
+
+        Self { state, index: N }
+    }
+
+    /// Seeds a generator with an array of integers (`init_by_array`)
+    pub fn from_array(key: &[u32]) -> Self {
+        let mut generator = Self::new(19_650_218);
+        let state = &mut generator.state;
+
+        let mut x = 1;
+        let mut y = 0;
+        for _ in 0..N.max(key.len()) {
+            let previous = state[x - 1] ^ (state[x - 1] >> 30);
+            state[x] = (state[x] ^ previous.wrapping_mul(1_664_525))
+                .wrapping_add(key.get(y).copied().unwrap_or(0))
+                .wrapping_add(y as u32);
+
+            x += 1;
+            y += 1;
+            if x >= N {
+                state[0] = state[N - 1];
+                x = 1;
+            }
+            if y >= key.len() {
+                y = 0;
+            }
+        }
+
+        for _ in 0..N - 1 {
+            let previous = state[x - 1] ^ (state[x - 1] >> 30);
+            state[x] = (state[x] ^ previous.wrapping_mul(1_566_083_941)).wrapping_sub(x as u32);
+
+            x += 1;
+            if x >= N {
+                state[0] = state[N - 1];
+                x = 1;
+            }
+        }
+
+        // Makes sure the state is never all zeros
+        state[0] = UPPER_MASK;
+        generator
+    }
+
+    /// Creates a generator from its (twisted) state words, its first output being
+    /// the tempering of the first word (e.g. a state recovered by untempering outputs)
+    pub const fn from_state(state: [u32; N]) -> Self {
+        Self { state, index: 0 }
+    }
+
+    /// Skips outputs, only tempering those which will actually be used
+    pub fn discard(&mut self, mut count: u64) {
+        loop {
+            let available = (N - self.index) as u64;
+            if count <= available {
+                self.index += count as usize;
+                return;
+            }
+
+            count -= available;
+            self.twist();
+        }
+    }
+
+    /// Generates the next 32 bit output
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+
+        let word = self.state[self.index];
+        self.index += 1;
+        temper(word)
+    }
+
+    /// Generates the next output in the range [0, 1) (with 32 bits of resolution)
+    pub fn next_f64(&mut self) -> f64 {
+        self.next_u32() as f64 / 4_294_967_296.0
+    }
+
+    /// Generates the next words of the state
+    fn twist(&mut self) {
+        for x in 0..N {
+            let y = (self.state[x] & UPPER_MASK) | (self.state[(x + 1) % N] & LOWER_MASK);
+            let mut word = self.state[(x + M) % N] ^ (y >> 1);
+            if y & 1 != 0 {
+                word ^= MATRIX_A;
+            }
+            self.state[x] = word;
+        }
+
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937 {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_seed() {
+        let mut generator = Mt19937::default();
+        assert_eq!(generator.next_u32(), 3_499_211_612);
+
+        // The C++ standard requires the 10000th output to be this
+        let mut generator = Mt19937::default();
+        let output = (0..10_000).map(|_| generator.next_u32()).last();
+        assert_eq!(output, Some(4_123_659_995));
+    }
+
+    #[test]
+    fn init_by_array() {
+        let mut generator = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+        let outputs: Vec<u32> = (0..5).map(|_| generator.next_u32()).collect();
+
+        // The reference implementation's (mt19937ar.c) first outputs
+        assert_eq!(
+            outputs,
+            [
+                1_067_595_299,
+                955_945_823,
+                477_289_528,
+                4_107_218_783,
+                4_228_976_476
+            ]
+        );
+    }
+
+    #[test]
+    fn next_f64() {
+        let mut generator = Mt19937::new(1);
+        assert!((0..1000).all(|_| (0.0..1.0).contains(&generator.next_f64())));
+    }
+
+    #[test]
+    fn from_state() {
+        let mut generator = Mt19937::new(42);
+        generator.discard(1);
+        let mut state = Mt19937::from_state(generator.state);
+        generator.index = 0;
+        assert_eq!(state, generator);
+        assert_eq!(state.next_u32(), generator.next_u32());
+    }
+
+    #[test]
+    fn discard() {
+        for count in [0, 1, N as u64 - 1, N as u64, N as u64 + 1, 3 * N as u64 + 7] {
+            let mut generator = Mt19937::new(7);
+            let mut reference = generator.clone();
+            generator.discard(count);
+            for _ in 0..count {
+                reference.next_u32();
+            }
+            assert_eq!(generator.next_u32(), reference.next_u32());
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::temper::temper_64;
+
+/// The number of words in the state
+const N: usize = 312;
+
+/// The offset of the word mixed into every twisted word
+const M: usize = 156;
+
+/// The twist matrix's last row
+const MATRIX_A: u64 = 0xB502_6F5A_A966_19E9;
+
+/// The 33 most significant bits
+const UPPER_MASK: u64 = 0xFFFF_FFFF_8000_0000;
+
+/// The 31 least significant bits
+const LOWER_MASK: u64 = 0x7FFF_FFFF;
+
+/// The 64 bit MT19937-64 Mersenne Twister
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mt19937_64 {
+    /// The state words
+    state: [u64; N],
+    /// The index of the next word to temper (the state is twisted once it reaches `N`)
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// The number of words in the state
+    pub const STATE_SIZE: usize = N;
+
+    /// The seed used by the reference implementation when none is given
+    pub const DEFAULT_SEED: u64 = 5489;
+
+    /// Seeds a generator with a 64 bit integer
+    pub fn new(seed: u64) -> Self {
+        let mut state = [0u64; N];
+        state[0] = seed;
+        for x in 1..N {
+            state[x] = 6_364_136_223_846_793_005u64
+                .wrapping_mul(state[x - 1] ^ (state[x - 1] >> 62))
+                .wrapping_add(x as u64);
+        }
+
+        Self { state, index: N }
+    }
+
+    /// Seeds a generator with an array of integers (`init_by_array64`)
+    pub fn from_array(key: &[u64]) -> Self {
+        let mut generator = Self::new(19_650_218);
+        let state = &mut generator.state;
+
+        let mut x = 1;
+        let mut y = 0;
+        for _ in 0..N.max(key.len()) {
+            let previous = state[x - 1] ^ (state[x - 1] >> 62);
+            state[x] = (state[x] ^ previous.wrapping_mul(3_935_559_000_370_003_845))
+                .wrapping_add(key.get(y).copied().unwrap_or(0))
+                .wrapping_add(y as u64);
+
+            x += 1;
+            y += 1;
+            if x >= N {
+                state[0] = state[N - 1];
+                x = 1;
+            }
+            if y >= key.len() {
+                y = 0;
+            }
+        }
+
+        for _ in 0..N - 1 {
+            let previous = state[x - 1] ^ (state[x - 1] >> 62);
+            state[x] = (state[x] ^ previous.wrapping_mul(2_862_933_555_777_941_757))
+                .wrapping_sub(x as u64);
+
+            x += 1;
+            if x >= N {
+                state[0] = state[N - 1];
+                x = 1;
+            }
+        }
+
+        // Makes sure the state is never all zeros
+        state[0] = 1 << 63;
+        generator
+    }
+
+    /// Creates a generator from its (twisted) state words, its first output being
+    /// the tempering of the first word (e.g. a state recovered by untempering outputs)
+    pub const fn from_state(state: [u64; N]) -> Self {
+        Self { state, index: 0 }
+    }
+
+    /// Skips outputs, only tempering those which will actually be used
+    pub fn discard(&mut self, mut count: u64) {
+        loop {
+            let available = (N - self.index) as u64;
+            if count <= available {
+                self.index += count as usize;
+                return;
+            }
+
+            count -= available;
+            self.twist();
+        }
+    }
+
+    /// Generates the next 64 bit output
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index >= N {
+            self.twist();
+        }
+
+        let word = self.state[self.index];
+        self.index += 1;
+        temper_64(word)
+    }
+
+    /// Generates the next output in the range [0, 1) (with 53 bits of resolution)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / 9_007_199_254_740_992.0
+    }
+
+    /// Generates the next words of the state
+    fn twist(&mut self) {
+        for x in 0..N {
+            let y = (self.state[x] & UPPER_MASK) | (self.state[(x + 1) % N] & LOWER_MASK);
+            let mut word = self.state[(x + M) % N] ^ (y >> 1);
+            if y & 1 != 0 {
+                word ^= MATRIX_A;
+            }
+            self.state[x] = word;
+        }
+
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937_64 {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_seed() {
+        let mut generator = Mt19937_64::default();
+        assert_eq!(generator.next_u64(), 14_514_284_786_278_117_030);
+
+        // The C++ standard requires the 10000th output to be this
+        let mut generator = Mt19937_64::default();
+        let output = (0..10_000).map(|_| generator.next_u64()).last();
+        assert_eq!(output, Some(9_981_545_732_273_789_042));
+    }
+
+    #[test]
+    fn init_by_array() {
+        let mut generator = Mt19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let outputs: Vec<u64> = (0..5).map(|_| generator.next_u64()).collect();
+
+        // The reference implementation's (mt19937-64.c) first outputs
+        assert_eq!(
+            outputs,
+            [
+                7_266_447_313_870_364_031,
+                4_946_485_549_665_804_864,
+                16_945_909_448_695_747_420,
+                16_394_063_075_524_226_720,
+                4_873_882_236_456_199_058
+            ]
+        );
+    }
+
+    #[test]
+    fn from_state() {
+        let mut generator = Mt19937_64::new(42);
+        generator.discard(1);
+        let mut state = Mt19937_64::from_state(generator.state);
+        generator.index = 0;
+        assert_eq!(state, generator);
+        assert_eq!(state.next_u64(), generator.next_u64());
+    }
+
+    #[test]
+    fn discard() {
+        for count in [0, 1, N as u64 - 1, N as u64, N as u64 + 1, 3 * N as u64 + 7] {
+            let mut generator = Mt19937_64::new(7);
+            let mut reference = generator.clone();
+            generator.discard(count);
+            for _ in 0..count {
+                reference.next_u64();
+            }
+            assert_eq!(generator.next_u64(), reference.next_u64());
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Mt19937, Mt19937_64};
+use rand_core::{impls, Error, RngCore, SeedableRng};
+
+impl RngCore for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        Self::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937 {
+    type Seed = [u8; 4];
+
+    /// Seeds the generator with a little endian 32 bit integer
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+}
+
+impl RngCore for Mt19937_64 {
+    /// The low half of the next 64 bit output
+    fn next_u32(&mut self) -> u32 {
+        Self::next_u64(self) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Self::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937_64 {
+    type Seed = [u8; 8];
+
+    /// Seeds the generator with a little endian 64 bit integer
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_core() {
+        let mut generator = Mt19937::from_seed(5489u32.to_le_bytes());
+        assert_eq!(RngCore::next_u32(&mut generator), 3_499_211_612);
+
+        let mut generator = Mt19937_64::from_seed(5489u64.to_le_bytes());
+        assert_eq!(
+            RngCore::next_u64(&mut generator),
+            14_514_284_786_278_117_030
+        );
+
+        // Bytes are taken from the outputs in little endian order
+        let mut generator = Mt19937::default();
+        let mut bytes = [0; 6];
+        generator.fill_bytes(&mut bytes);
+        let mut reference = Mt19937::default();
+        let expected = [
+            reference.next_u32().to_le_bytes(),
+            reference.next_u32().to_le_bytes(),
+        ];
+        assert_eq!(bytes, expected.concat()[..6]);
+    }
+}
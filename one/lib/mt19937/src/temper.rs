@@ -0,0 +1,77 @@
+/// Tempers a 32 bit MT19937 state word into an output
+pub const fn temper(word: u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C_5680;
+    y ^= (y << 15) & 0xEFC6_0000;
+    y ^= y >> 18;
+    y
+}
+
+/// Recovers the 32 bit MT19937 state word an output was tempered from
+pub const fn untemper(output: u32) -> u32 {
+    let mut y = output as u64;
+    y = undo_right(y, 18, 0xFFFF_FFFF, 32);
+    y = undo_left(y, 15, 0xEFC6_0000, 32);
+    y = undo_left(y, 7, 0x9D2C_5680, 32);
+    y = undo_right(y, 11, 0xFFFF_FFFF, 32);
+    y as u32
+}
+
+/// Tempers a 64 bit MT19937-64 state word into an output
+pub const fn temper_64(word: u64) -> u64 {
+    let mut y = word;
+    y ^= (y >> 29) & 0x5555_5555_5555_5555;
+    y ^= (y << 17) & 0x71D6_7FFF_EDA6_0000;
+    y ^= (y << 37) & 0xFFF7_EEE0_0000_0000;
+    y ^= y >> 43;
+    y
+}
+
+/// Recovers the 64 bit MT19937-64 state word an output was tempered from
+pub const fn untemper_64(output: u64) -> u64 {
+    let mut y = output;
+    y = undo_right(y, 43, u64::MAX, 64);
+    y = undo_left(y, 37, 0xFFF7_EEE0_0000_0000, 64);
+    y = undo_left(y, 17, 0x71D6_7FFF_EDA6_0000, 64);
+    y = undo_right(y, 29, 0x5555_5555_5555_5555, 64);
+    y
+}
+
+/// Inverts `y ^= (y >> shift) & mask`, every pass recovers `shift` more of the top bits
+const fn undo_right(output: u64, shift: u32, mask: u64, bits: u32) -> u64 {
+    let mut y = output;
+    let mut x = 0;
+    while x < bits.div_ceil(shift) {
+        y = output ^ ((y >> shift) & mask);
+        x += 1;
+    }
+    y
+}
+
+/// Inverts `y ^= (y << shift) & mask`, every pass recovers `shift` more of the
+/// bottom bits (the mask keeps 32 bit words from growing past 32 bits)
+const fn undo_left(output: u64, shift: u32, mask: u64, bits: u32) -> u64 {
+    let mut y = output;
+    let mut x = 0;
+    while x < bits.div_ceil(shift) {
+        y = output ^ ((y << shift) & mask);
+        x += 1;
+    }
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untemper_inverts_temper() {
+        for word in [0, 1, 0x8000_0000, 0xDEAD_BEEF, u32::MAX, 0x1234_5678] {
+            assert_eq!(untemper(temper(word)), word);
+        }
+        for word in [0, 1, 1 << 63, 0xDEAD_BEEF_CAFE_BABE, u64::MAX] {
+            assert_eq!(untemper_64(temper_64(word)), word);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+/// The errors that can occur while hashing with SHA-1
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Sha1Error {
+    /// A hasher can only be resumed after whole blocks
+    InvalidLength,
+}
+
+/// The result type used throughout the crate
+pub type Sha1Result<T> = Result<T, Sha1Error>;
+
+impl fmt::Display for Sha1Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidLength => "length is not made of whole blocks",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for Sha1Error {}
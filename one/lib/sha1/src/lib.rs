@@ -0,0 +1,16 @@
+#![warn(
+    clippy::all,
+    clippy::nursery,
+    clippy::missing_docs_in_private_items,
+    missing_docs
+)]
+
+//! A SHA-1 crate, whose hashers can be resumed from a digest
+
+/// A module containing the crate's errors
+mod errors;
+/// A module dedicated to the SHA-1 hash function
+mod sha1;
+
+pub use errors::{Sha1Error, Sha1Result};
+pub use sha1::{Sha1, BLOCK_SIZE, DIGEST_SIZE};
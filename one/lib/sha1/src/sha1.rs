@@ -0,0 +1,242 @@
+use crate::errors::*;
+
+/// The size of the blocks the message is processed in (in bytes)
+pub const BLOCK_SIZE: usize = 64;
+
+/// The size of a digest (in bytes)
+pub const DIGEST_SIZE: usize = 20;
+
+/// The initial state
+const INITIAL_STATE: [u32; 5] = [
+    0x6745_2301,
+    0xEFCD_AB89,
+    0x98BA_DCFE,
+    0x1032_5476,
+    0xC3D2_E1F0,
+];
+
+/// A streaming SHA-1 hasher
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sha1 {
+    /// The chaining state (which the digest is made of)
+    state: [u32; 5],
+    /// The bytes which don't fill a block yet
+    buffer: Vec<u8>,
+    /// The length of the message so far (in bytes)
+    length: u64,
+}
+
+impl Sha1 {
+    /// Creates a hasher for a new message
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            length: 0,
+        }
+    }
+
+    /// Hashes a whole message at once
+    pub fn digest(message: &[u8]) -> [u8; DIGEST_SIZE] {
+        let mut hasher = Self::new();
+        hasher.update(message);
+        hasher.finalize()
+    }
+
+    /// Resumes hashing from a digest, as if `length` bytes (the original
+    /// message and its glue padding) had already been processed
+    ///
+    /// The digest is the whole state, so whoever knows `SHA1(secret || message)`
+    /// can append to the padded message and compute the new digest, without the secret
+    pub fn from_state(digest: &[u8; DIGEST_SIZE], length: u64) -> Sha1Result<Self> {
+        if !length.is_multiple_of(BLOCK_SIZE as u64) {
+            return Err(Sha1Error::InvalidLength);
+        }
+
+        let mut state = [0; 5];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+
+        Ok(Self {
+            state,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            length,
+        })
+    }
+
+    /// The padding appended to a message of a given length (in bytes) before it's
+    /// hashed, a `0x80` byte, zeros, and the message's length in bits (big endian)
+    pub fn glue_padding(length: u64) -> Vec<u8> {
+        let zeros = (BLOCK_SIZE * 2 - 9 - (length % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE;
+
+        let mut padding = Vec::with_capacity(zeros + 9);
+        padding.push(0x80);
+        padding.resize(zeros + 1, 0);
+        padding.extend(length.wrapping_mul(8).to_be_bytes());
+        padding
+    }
+
+    /// The length of the message so far (in bytes)
+    pub const fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Appends bytes to the message
+    pub fn update(&mut self, data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let missing = (BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+
+            if self.buffer.len() < BLOCK_SIZE {
+                return;
+            }
+
+            let block: [u8; BLOCK_SIZE] = self.buffer.as_slice().try_into().unwrap();
+            self.buffer.clear();
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    /// Pads the message and returns its digest
+    pub fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let length = self.length;
+        self.update(&Self::glue_padding(length));
+
+        let mut digest = [0; DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    /// Processes a single block
+    fn compress(&mut self, block: &[u8; BLOCK_SIZE]) {
+        let mut schedule = [0u32; 80];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        for x in 16..80 {
+            schedule[x] = (schedule[x - 3] ^ schedule[x - 8] ^ schedule[x - 14] ^ schedule[x - 16])
+                .rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+        for (x, word) in schedule.into_iter().enumerate() {
+            let (f, k) = match x {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            (a, b, c, d, e) = (temp, a, b.rotate_left(30), c, d);
+        }
+
+        for (word, x) in self.state.iter_mut().zip([a, b, c, d, e]) {
+            *word = word.wrapping_add(x);
+        }
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding::Encoding;
+
+    #[test]
+    fn fips_180_vectors() {
+        assert_eq!(
+            Sha1::digest(b"abc").to_vec(),
+            Encoding::Hex
+                .decode(b"a9993e364706816aba3e25717850c26c9cd0d89d")
+                .unwrap()
+        );
+        assert_eq!(
+            Sha1::digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").to_vec(),
+            Encoding::Hex
+                .decode(b"84983e441c3bd26ebaae4aa1f95129e5e54670f1")
+                .unwrap()
+        );
+        assert_eq!(
+            Sha1::digest(&vec![b'a'; 1_000_000]).to_vec(),
+            Encoding::Hex
+                .decode(b"34aa973cd4c4daa4f61eeb2bdbad27316534016f")
+                .unwrap()
+        );
+        assert_eq!(
+            Sha1::digest(b"").to_vec(),
+            Encoding::Hex
+                .decode(b"da39a3ee5e6b4b0d3255bfef95601890afd80709")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let message: Vec<u8> = (0..300).map(|x| x as u8).collect();
+        let expected = Sha1::digest(&message);
+
+        for split in [0, 1, 55, 63, 64, 65, 128, 299] {
+            let mut hasher = Sha1::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..split + 1]);
+            hasher.update(&message[split + 1..]);
+            assert_eq!(hasher.length(), 300);
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+
+    #[test]
+    fn glue_padding() {
+        for length in [0, 1, 55, 56, 63, 64, 119, 1000] {
+            let padding = Sha1::glue_padding(length);
+            assert_eq!((length + padding.len() as u64) % BLOCK_SIZE as u64, 0);
+            assert!((9..=BLOCK_SIZE + 8).contains(&padding.len()));
+            assert_eq!(padding[0], 0x80);
+            assert_eq!(padding[padding.len() - 8..], (length * 8).to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn length_extension() {
+        // A secret prefix MAC, whose secret's length is the only thing the attacker guesses
+        let secret = b"YELLOW SUBMARINE";
+        let message =
+            b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let mac = Sha1::digest(&[&secret[..], message].concat());
+
+        let length = (secret.len() + message.len()) as u64;
+        let glue = Sha1::glue_padding(length);
+        let mut hasher = Sha1::from_state(&mac, length + glue.len() as u64).unwrap();
+        hasher.update(b";admin=true");
+        let forged_mac = hasher.finalize();
+
+        let forged = [&message[..], &glue, b";admin=true"].concat();
+        assert_eq!(Sha1::digest(&[&secret[..], &forged].concat()), forged_mac);
+
+        assert_eq!(Sha1::from_state(&mac, 65), Err(Sha1Error::InvalidLength));
+    }
+}
@@ -0,0 +1,24 @@
+//! Compares the hamming distance with and without the `simd` feature:
+//! `cargo bench -p xor` and `cargo bench -p xor --features simd`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use xor::Xor;
+
+fn hamming_distance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hamming_distance_bytes");
+
+    for length in [64, 4096, 1 << 20] {
+        let lvalue: Vec<u8> = (0..length).map(|x| (x * 31) as u8).collect();
+        let rvalue: Vec<u8> = (0..length).map(|x| (x * 17) as u8 ^ 0x5A).collect();
+
+        group.throughput(Throughput::Bytes(length as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(length), &length, |b, _| {
+            b.iter(|| Xor::hamming_distance_bytes(black_box(&lvalue), black_box(&rvalue)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, hamming_distance);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+use crate::errors::*;
+use crate::key_length::KeyLengthEstimator;
+use crate::scorer::{English, Scorer};
+use crate::xor::Xor;
+
+/// Options for breaking repeating key XOR
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BreakOptions {
+    /// The longest key length which should be considered
+    pub max_key_length: usize,
+    /// The estimators used to rank the possible key lengths
+    pub estimators: Vec<KeyLengthEstimator>,
+    /// How many of the most probable key lengths should be tried
+    pub key_length_candidates: usize,
+}
+
+impl Default for BreakOptions {
+    fn default() -> Self {
+        Self {
+            max_key_length: 40,
+            estimators: KeyLengthEstimator::ALL.to_vec(),
+            key_length_candidates: 3,
+        }
+    }
+}
+
+/// The result of breaking a repeating key XOR ciphertext
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakResult {
+    /// The recovered key
+    pub key: Vec<u8>,
+    /// The length of the recovered key
+    pub key_length: usize,
+    /// The recovered plaintext
+    pub plaintext: Vec<u8>,
+    /// The plaintext's score (between 0 and 1)
+    pub score: f64,
+}
+
+impl Xor {
+    /// Breaks a repeating key XOR ciphertext, recovering both the key and the plaintext
+    pub fn break_repeating_key(ciphertext: &[u8], opts: &BreakOptions) -> XorResult<BreakResult> {
+        Self::break_repeating_key_with(ciphertext, opts, &English)
+    }
+
+    /// Breaks a repeating key XOR ciphertext, rating
+    /// each possible plaintext with the given scorer
+    ///
+    /// Every one of the most probable key lengths is tried,
+    /// and the plaintext with the highest score is kept
+    pub fn break_repeating_key_with<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        opts: &BreakOptions,
+        scorer: &S,
+    ) -> XorResult<BreakResult> {
+        let ranking =
+            Self::rank_key_lengths_combined(ciphertext, opts.max_key_length, &opts.estimators)?;
+
+        let mut best: Option<BreakResult> = None;
+        for key_length in ranking.iter().take(opts.key_length_candidates.max(1)) {
+            let result = Self::break_key_length(ciphertext, key_length.length, scorer)?;
+
+            // Keeps the first of any equally probable results
+            if best.as_ref().is_none_or(|x| result.score > x.score) {
+                best = Some(result);
+            }
+        }
+
+        best.ok_or(XorError::ProbabilityCalc)
+    }
+
+    /// Breaks a repeating key XOR ciphertext assuming a given key length
+    fn break_key_length<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        key_length: usize,
+        scorer: &S,
+    ) -> XorResult<BreakResult> {
+        // Every column of the transposed blocks was XORed with the same key byte
+        let blocks = Self::block_ciphertext(ciphertext, key_length);
+        let columns = Self::transpose_blocks(&blocks, key_length);
+        let key = columns
+            .iter()
+            .map(|column| Ok(Self::single_byte_bruteforce_with(column, scorer)?.key))
+            .collect::<XorResult<Vec<u8>>>()?;
+
+        // The estimate may be a multiple of the real key length, in
+        // which case the key is made up of a shorter repeating key
+        let key_length = (1..=key.len())
+            .find(|&x| key.len().is_multiple_of(x) && key.chunks(x).all(|y| y == &key[..x]))
+            .unwrap_or(key.len());
+        let key = key[..key_length].to_vec();
+
+        let plaintext: Vec<u8> = ciphertext
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(x, y)| x ^ y)
+            .collect();
+
+        Ok(BreakResult {
+            key,
+            key_length,
+            score: scorer.score(&plaintext),
+            plaintext,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_repeating_key() {
+        let plaintext = &b"Burning 'em, if you ain't quick and nimble. I go crazy when I hear \
+            a cymbal and a hi-hat with a souped up tempo. I'm on a roll, it's time to go solo. \
+            Ice ice baby, with the bass kicked in and the vega's are pumpin'. Quick to the point, \
+            to the point, no faking, cooking MC's like a pound of bacon. "
+            .repeat(4);
+        let key = b"Vanilla";
+
+        let ciphertext: Vec<u8> = plaintext
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(x, y)| x ^ y)
+            .collect();
+
+        let result = Xor::break_repeating_key(&ciphertext, &BreakOptions::default()).unwrap();
+        assert_eq!(result.key, key);
+        assert_eq!(result.key_length, key.len());
+        assert_eq!(&result.plaintext, plaintext);
+    }
+
+    #[test]
+    fn tries_several_key_lengths() {
+        let plaintext = b"Quick to the point, to the point, no faking, cooking MC's like a pound \
+            of bacon. Burning 'em, if you ain't quick and nimble, I go crazy when I hear a cymbal."
+            .repeat(2);
+        let key = b"ICE";
+
+        let ciphertext: Vec<u8> = plaintext
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(x, y)| x ^ y)
+            .collect();
+
+        let opts = BreakOptions {
+            estimators: vec![KeyLengthEstimator::Hamming],
+            key_length_candidates: 10,
+            ..Default::default()
+        };
+        let result = Xor::break_repeating_key(&ciphertext, &opts).unwrap();
+        assert_eq!(result.key, key);
+        assert_eq!(result.plaintext, plaintext);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::errors::*;
+use crate::scorer::{Fragment, Scorer};
+use crate::xor::Xor;
+
+/// A position a crib might appear at
+#[derive(Clone, Debug, PartialEq)]
+pub struct CribMatch {
+    /// Where the crib was placed
+    pub offset: usize,
+    /// The average score of the revealed fragments (between 0 and 1)
+    pub score: f64,
+    /// The plaintext fragments the crib reveals in the other ciphertexts
+    pub revealed: Vec<Vec<u8>>,
+}
+
+/// A keystream shared by several ciphertexts which has only partially been recovered
+///
+/// Cribs can be tried, placed and cleared again, so that
+/// the keystream is refined a guess at a time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialKeystream {
+    /// The ciphertexts encrypted with the keystream
+    ciphertexts: Vec<Vec<u8>>,
+    /// The recovered keystream bytes (`None` where they're still unknown)
+    keystream: Vec<Option<u8>>,
+}
+
+impl Xor {
+    /// Slides a crib across the XOR of two ciphertexts encrypted with the same keystream
+    ///
+    /// Wherever the crib lines up with one plaintext, the other plaintext
+    /// is revealed, matches are rated with the `Fragment` scorer and
+    /// sorted by descending score (and then by offset)
+    pub fn crib_drag(lvalue: &[u8], rvalue: &[u8], crib: &[u8]) -> Vec<CribMatch> {
+        Self::crib_drag_with(lvalue, rvalue, crib, &Fragment)
+    }
+
+    /// Slides a crib across the XOR of two ciphertexts, rating
+    /// each revealed fragment with the given scorer
+    pub fn crib_drag_with<S: Scorer + ?Sized>(
+        lvalue: &[u8],
+        rvalue: &[u8],
+        crib: &[u8],
+        scorer: &S,
+    ) -> Vec<CribMatch> {
+        // The keystream cancels out, leaving the XOR of both plaintexts
+        let combined: Vec<u8> = lvalue.iter().zip(rvalue).map(|(x, y)| x ^ y).collect();
+        if crib.is_empty() || crib.len() > combined.len() {
+            return vec![];
+        }
+
+        let mut matches: Vec<CribMatch> = (0..=combined.len() - crib.len())
+            .map(|offset| {
+                let revealed: Vec<u8> = combined[offset..offset + crib.len()]
+                    .iter()
+                    .zip(crib)
+                    .map(|(x, y)| x ^ y)
+                    .collect();
+
+                CribMatch {
+                    offset,
+                    score: scorer.score(&revealed),
+                    revealed: vec![revealed],
+                }
+            })
+            .collect();
+
+        sort_matches(&mut matches);
+        matches
+    }
+}
+
+impl PartialKeystream {
+    /// Starts recovering the keystream shared by the given ciphertexts
+    pub fn new(ciphertexts: Vec<Vec<u8>>) -> Self {
+        let length = ciphertexts.iter().map(Vec::len).max().unwrap_or(0);
+
+        Self {
+            ciphertexts,
+            keystream: vec![None; length],
+        }
+    }
+
+    /// The keystream recovered so far
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    /// The ciphertexts encrypted with the keystream
+    pub fn ciphertexts(&self) -> &[Vec<u8>] {
+        &self.ciphertexts
+    }
+
+    /// Slides a crib across one of the ciphertexts, revealing the other ciphertexts
+    /// at every offset, matches are sorted by descending score (and then by offset)
+    pub fn drag<S: Scorer + ?Sized>(
+        &self,
+        index: usize,
+        crib: &[u8],
+        scorer: &S,
+    ) -> XorResult<Vec<CribMatch>> {
+        let ciphertext = self.ciphertexts.get(index).ok_or(XorError::OutOfBounds)?;
+        if crib.is_empty() || crib.len() > ciphertext.len() {
+            return Ok(vec![]);
+        }
+
+        let mut matches: Vec<CribMatch> = vec![];
+        for offset in 0..=ciphertext.len() - crib.len() {
+            let keystream = self.guess_keystream(index, offset, crib)?;
+
+            // Decrypts whatever part of the other ciphertexts the guess covers
+            let revealed: Vec<Vec<u8>> = self
+                .ciphertexts
+                .iter()
+                .enumerate()
+                .filter(|&(x, other)| x != index && other.len() > offset)
+                .map(|(_, other)| {
+                    other[offset..]
+                        .iter()
+                        .zip(&keystream)
+                        .map(|(x, y)| x ^ y)
+                        .collect()
+                })
+                .collect();
+
+            let score = if revealed.is_empty() {
+                0.0
+            } else {
+                revealed.iter().map(|x| scorer.score(x)).sum::<f64>() / revealed.len() as f64
+            };
+
+            matches.push(CribMatch {
+                offset,
+                score,
+                revealed,
+            });
+        }
+
+        sort_matches(&mut matches);
+        Ok(matches)
+    }
+
+    /// Places a known plaintext in one of the ciphertexts,
+    /// recovering (or overwriting) that part of the keystream
+    pub fn place(&mut self, index: usize, offset: usize, plaintext: &[u8]) -> XorResult<()> {
+        let keystream = self.guess_keystream(index, offset, plaintext)?;
+        for (x, byte) in keystream.into_iter().enumerate() {
+            self.keystream[offset + x] = Some(byte);
+        }
+
+        Ok(())
+    }
+
+    /// Forgets part of the keystream, e.g. to undo a wrong guess
+    pub fn clear(&mut self, offset: usize, length: usize) -> XorResult<()> {
+        let end = offset.checked_add(length).ok_or(XorError::OutOfBounds)?;
+        self.keystream
+            .get_mut(offset..end)
+            .ok_or(XorError::OutOfBounds)?
+            .fill(None);
+
+        Ok(())
+    }
+
+    /// Decrypts one of the ciphertexts with the keystream
+    /// recovered so far (`None` where it's still unknown)
+    pub fn decrypt(&self, index: usize) -> XorResult<Vec<Option<u8>>> {
+        let ciphertext = self.ciphertexts.get(index).ok_or(XorError::OutOfBounds)?;
+
+        Ok(ciphertext
+            .iter()
+            .zip(&self.keystream)
+            .map(|(x, y)| y.map(|y| x ^ y))
+            .collect())
+    }
+
+    /// Decrypts one of the ciphertexts with the keystream recovered
+    /// so far, replacing every unknown byte with a placeholder
+    pub fn render(&self, index: usize, placeholder: u8) -> XorResult<Vec<u8>> {
+        Ok(self
+            .decrypt(index)?
+            .into_iter()
+            .map(|x| x.unwrap_or(placeholder))
+            .collect())
+    }
+
+    /// Calculates the keystream that would turn part of
+    /// a ciphertext into the given plaintext
+    fn guess_keystream(&self, index: usize, offset: usize, plaintext: &[u8]) -> XorResult<Vec<u8>> {
+        let ciphertext = self.ciphertexts.get(index).ok_or(XorError::OutOfBounds)?;
+        let end = offset
+            .checked_add(plaintext.len())
+            .ok_or(XorError::OutOfBounds)?;
+
+        Ok(ciphertext
+            .get(offset..end)
+            .ok_or(XorError::OutOfBounds)?
+            .iter()
+            .zip(plaintext)
+            .map(|(x, y)| x ^ y)
+            .collect())
+    }
+}
+
+/// Sorts crib matches by descending score, preferring earlier offsets on ties
+fn sort_matches(matches: &mut [CribMatch]) {
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.offset.cmp(&b.offset)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two plaintexts encrypted with the same keystream
+    const PLAINTEXTS: [&[u8]; 2] = [
+        b"We attack the northern gate at dawn tomorrow",
+        b"Send more supplies to the eastern camp today",
+    ];
+
+    /// Encrypts the plaintexts with a (not so) random keystream
+    fn ciphertexts() -> Vec<Vec<u8>> {
+        let keystream: Vec<u8> = (0..64u8).map(|x| x.wrapping_mul(167) ^ 0x3C).collect();
+
+        PLAINTEXTS
+            .iter()
+            .map(|x| x.iter().zip(&keystream).map(|(x, y)| x ^ y).collect())
+            .collect()
+    }
+
+    #[test]
+    fn crib_drag() {
+        let ciphertexts = ciphertexts();
+
+        let matches = Xor::crib_drag(&ciphertexts[0], &ciphertexts[1], b" the ");
+        assert_eq!(matches.len(), PLAINTEXTS[0].len() - 4);
+        assert!(matches.windows(2).all(|x| x[0].score >= x[1].score));
+
+        // " the " appears at offset 21 of the second plaintext, which ranks first,
+        // and at offset 9 of the first, whose " supp" fragment is outranked by "heu+t"
+        let offsets: Vec<usize> = matches.iter().take(3).map(|x| x.offset).collect();
+        assert_eq!(offsets, [21, 18, 9]);
+        assert_eq!(matches[0].revealed, [b"n gat".to_vec()]);
+        assert_eq!(matches[2].revealed, [b" supp".to_vec()]);
+
+        assert!(Xor::crib_drag(&ciphertexts[0], &ciphertexts[1], b"").is_empty());
+        assert!(Xor::crib_drag(b"ab", b"cd", b"abc").is_empty());
+    }
+
+    #[test]
+    fn refine_keystream() {
+        let mut keystream = PartialKeystream::new(ciphertexts());
+
+        let matches = keystream.drag(1, b" the ", &Fragment).unwrap();
+        assert_eq!(matches[0].offset, 21);
+        assert_eq!(matches[0].revealed, [b"n gat".to_vec()]);
+
+        keystream.place(1, 21, b" the ").unwrap();
+        assert_eq!(
+            keystream.render(0, b'*').unwrap(),
+            b"*********************n gat******************"
+        );
+
+        // Wrong guesses can be undone
+        keystream.place(0, 0, b"We attack").unwrap();
+        keystream.clear(21, 5).unwrap();
+        assert_eq!(
+            keystream.render(1, b'*').unwrap(),
+            b"Send more***********************************"
+        );
+        assert_eq!(keystream.decrypt(1).unwrap()[9], None);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut keystream = PartialKeystream::new(ciphertexts());
+
+        assert!(matches!(
+            keystream.place(0, 40, b"dawn tomorrow"),
+            Err(XorError::OutOfBounds)
+        ));
+        assert!(matches!(
+            keystream.place(2, 0, b"a"),
+            Err(XorError::OutOfBounds)
+        ));
+        assert!(matches!(
+            keystream.clear(60, 10),
+            Err(XorError::OutOfBounds)
+        ));
+        assert!(matches!(
+            keystream.drag(2, b"a", &Fragment),
+            Err(XorError::OutOfBounds)
+        ));
+    }
+}
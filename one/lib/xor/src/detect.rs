@@ -0,0 +1,160 @@
+use crate::errors::*;
+use crate::scorer::Scorer;
+use crate::xor::Xor;
+use rayon::prelude::*;
+use std::io::BufRead;
+
+/// How many lines are read into memory and bruteforced in parallel at a time
+const BATCH_SIZE: usize = 1 << 16;
+
+/// A line of a ciphertext list which may have been single byte XORed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+    /// The line number (starting from 1)
+    pub line: usize,
+    /// The key byte the line was XORed with
+    pub key: u8,
+    /// The plaintext's score (between 0 and 1)
+    pub score: f64,
+    /// The possible plaintext
+    pub plaintext: Vec<u8>,
+}
+
+impl Xor {
+    /// Bruteforces every line of a ciphertext list in parallel and returns
+    /// the `n` lines most likely to be single byte XORed, according to the given scorer
+    ///
+    /// Detections are sorted by descending score, equal scores
+    /// are sorted by ascending line number
+    pub fn detect_single_byte_xor<T: BufRead, S: Scorer + Sync + ?Sized>(
+        reader: T,
+        hex: bool,
+        n: usize,
+        scorer: &S,
+    ) -> XorResult<Vec<Detection>> {
+        Self::detect_in_batches(reader, hex, n, scorer, BATCH_SIZE)
+    }
+
+    /// Reads and bruteforces a ciphertext list a batch of lines at a time,
+    /// so that only the current batch and the best detections are kept in memory
+    fn detect_in_batches<T: BufRead, S: Scorer + Sync + ?Sized>(
+        reader: T,
+        hex: bool,
+        n: usize,
+        scorer: &S,
+        batch_size: usize,
+    ) -> XorResult<Vec<Detection>> {
+        let mut best: Vec<Detection> = vec![];
+        let mut lines = reader.lines();
+        let mut offset = 0;
+
+        loop {
+            let batch = lines
+                .by_ref()
+                .take(batch_size)
+                .collect::<Result<Vec<String>, _>>()?;
+            if batch.is_empty() {
+                break;
+            }
+
+            // Bruteforces the batch
+            let detections = batch
+                .par_iter()
+                .enumerate()
+                .map(|(x, ciphertext)| {
+                    let candidate = if hex {
+                        Self::single_byte_bruteforce_with(&hex::decode(ciphertext)?, scorer)?
+                    } else {
+                        Self::single_byte_bruteforce_with(ciphertext.as_bytes(), scorer)?
+                    };
+
+                    Ok(Detection {
+                        line: offset + x + 1,
+                        key: candidate.key,
+                        score: candidate.score,
+                        plaintext: candidate.plaintext,
+                    })
+                })
+                .collect::<XorResult<Vec<Detection>>>()?;
+
+            // Keeps the best detections so far
+            best.extend(detections);
+            best.par_sort_by(|a, b| b.score.total_cmp(&a.score).then(a.line.cmp(&b.line)));
+            best.truncate(n);
+
+            offset += batch.len();
+        }
+
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorer::English;
+
+    /// A list of hex encoded ciphertexts, the third line is
+    /// the only one which was XORed from english text
+    const CIPHERTEXTS: &str = "0e3647e8d3d97b7a08d01b8a43ea04f47b5c0b4c8d33b12a0305f8c12e3b
+        7a6c2e0e16b6df24d0d0bbd3f37f4bbc5ff2a4bff4c8f4d6a7e1b1d5e3a1
+        7b5a4215415d544115415d5015455447414c155c46155f4058455c5b523f
+        1d2c0d6d2e4cd9fe0c1a4c6a5b54e35a1ba98a6b80fb81a3a1da9d1f11d6";
+
+    /// Trims the indentation of the ciphertext list
+    fn ciphertexts() -> String {
+        CIPHERTEXTS
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn detects_the_xored_line() {
+        let detections =
+            Xor::detect_single_byte_xor(ciphertexts().as_bytes(), true, 1, &English).unwrap();
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].line, 3);
+        assert_eq!(detections[0].key, 0x35);
+        assert_eq!(detections[0].plaintext, b"Now that the party is jumping\n");
+    }
+
+    #[test]
+    fn batches_match_a_single_pass() {
+        let ciphertexts = ciphertexts();
+
+        let single = Xor::detect_in_batches(ciphertexts.as_bytes(), true, 4, &English, 100);
+        for batch_size in 1..4 {
+            let batched =
+                Xor::detect_in_batches(ciphertexts.as_bytes(), true, 4, &English, batch_size);
+            assert_eq!(batched.unwrap(), *single.as_ref().unwrap());
+        }
+    }
+
+    #[test]
+    fn ties_are_kept() {
+        let ciphertexts = "abc\nxyz\nabc\nabc";
+
+        let detections =
+            Xor::detect_single_byte_xor(ciphertexts.as_bytes(), false, 10, &English).unwrap();
+        let ties: Vec<&Detection> = detections.iter().filter(|x| x.line != 2).collect();
+
+        // Equal lines are all reported, in order
+        assert_eq!(detections.len(), 4);
+        assert_eq!(
+            ties.iter().map(|x| x.line).collect::<Vec<usize>>(),
+            [1, 3, 4]
+        );
+        assert!(ties.iter().all(|x| x.score == ties[0].score));
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert!(matches!(
+            Xor::detect_single_byte_xor("00ff\nzz".as_bytes(), true, 1, &English),
+            Err(XorError::HexConversion)
+        ));
+    }
+}
@@ -13,6 +13,14 @@ pub enum XorError {
     Utf8Conversion,
     /// Something went wrong during the probability calculation
     ProbabilityCalc,
+    /// A language model was malformed or had an unsupported order
+    InvalidModel,
+    /// The input was too short to be analysed (e.i for a key length estimate)
+    InsufficientData,
+    /// A key (or the longest key length to consider) was empty
+    InvalidKeyLength,
+    /// A position (or index) was past the end of an input
+    OutOfBounds,
 }
 
 pub type XorResult<T> = Result<T, XorError>;
@@ -0,0 +1,311 @@
+use crate::errors::*;
+use crate::scorer::{English, Scorer};
+use crate::xor::Xor;
+
+/// How many bytes are decrypted to shortlist the keys of large key spaces
+const SAMPLE_LENGTH: usize = 32;
+
+/// How many keys are shortlisted before decrypting the whole ciphertext
+const SHORTLIST_LENGTH: usize = 16;
+
+/// A key (of any XOR family) recovered along with its plaintext
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recovered<K> {
+    /// The recovered key
+    pub key: K,
+    /// The plaintext's score (between 0 and 1)
+    pub score: f64,
+    /// The recovered plaintext
+    pub plaintext: Vec<u8>,
+}
+
+/// What every byte is XORed with after the first one (which is XORed with the key)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Autokey {
+    /// The previous plaintext byte
+    Plaintext,
+    /// The previous ciphertext byte
+    Ciphertext,
+}
+
+/// A linear congruential generator (modulo 2^32) whose
+/// shifted states are used as a keystream
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Lcg {
+    /// The multiplier applied to the state
+    pub multiplier: u32,
+    /// The increment added to the state
+    pub increment: u32,
+    /// How far the state is shifted right before taking its lowest byte
+    pub shift: u32,
+}
+
+impl Lcg {
+    /// The generator used by the Microsoft C runtime's `rand`
+    pub const MSVC: Self = Self {
+        multiplier: 214_013,
+        increment: 2_531_011,
+        shift: 16,
+    };
+
+    /// Generates a keystream of a given length, advancing the state before every byte
+    pub fn keystream(&self, seed: u32, length: usize) -> Vec<u8> {
+        let mut state = seed;
+        (0..length)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(self.multiplier)
+                    .wrapping_add(self.increment);
+                // Shifting every bit out leaves nothing (rather than overflowing)
+                state.checked_shr(self.shift).unwrap_or(0) as u8
+            })
+            .collect()
+    }
+
+    /// The number of low seed bits which determine the keystream,
+    /// since no bit of the state depends on the bits above it
+    pub const fn seed_bits(&self) -> u32 {
+        if self.shift.saturating_add(8) > u32::BITS {
+            u32::BITS
+        } else {
+            self.shift + 8
+        }
+    }
+}
+
+impl Xor {
+    /// XORs an input with a key which is incremented by `step` after every byte
+    pub fn incrementing_key(input: &[u8], key: u8, step: u8) -> Vec<u8> {
+        input
+            .iter()
+            .enumerate()
+            .map(|(x, byte)| byte ^ key.wrapping_add(step.wrapping_mul(x as u8)))
+            .collect()
+    }
+
+    /// Breaks an incrementing key XOR ciphertext, recovering the key and step
+    pub fn break_incrementing_key(ciphertext: &[u8]) -> XorResult<Recovered<(u8, u8)>> {
+        Self::break_incrementing_key_with(ciphertext, &English)
+    }
+
+    /// Breaks an incrementing key XOR ciphertext, rating
+    /// each possible plaintext with the given scorer
+    pub fn break_incrementing_key_with<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        scorer: &S,
+    ) -> XorResult<Recovered<(u8, u8)>> {
+        let keys = (0..=u8::MAX).flat_map(|key| (0..=u8::MAX).map(move |step| (key, step)));
+
+        Self::shortlist_keys(keys, ciphertext, scorer, |input, (key, step)| {
+            Self::incrementing_key(input, key, step)
+        })
+    }
+
+    /// Encrypts an input with an autokey XOR, the first byte is XORed with
+    /// the key and every other byte with the previous plaintext or ciphertext byte
+    pub fn autokey_encrypt(input: &[u8], key: u8, mode: Autokey) -> Vec<u8> {
+        let mut previous = key;
+        input
+            .iter()
+            .map(|&byte| {
+                let output = byte ^ previous;
+                previous = match mode {
+                    Autokey::Plaintext => byte,
+                    Autokey::Ciphertext => output,
+                };
+                output
+            })
+            .collect()
+    }
+
+    /// Decrypts an autokey XOR ciphertext
+    pub fn autokey_decrypt(input: &[u8], key: u8, mode: Autokey) -> Vec<u8> {
+        let mut previous = key;
+        input
+            .iter()
+            .map(|&byte| {
+                let output = byte ^ previous;
+                previous = match mode {
+                    Autokey::Plaintext => output,
+                    Autokey::Ciphertext => byte,
+                };
+                output
+            })
+            .collect()
+    }
+
+    /// Breaks an autokey XOR ciphertext, recovering the key byte
+    pub fn break_autokey(ciphertext: &[u8], mode: Autokey) -> XorResult<Recovered<u8>> {
+        Self::break_autokey_with(ciphertext, mode, &English)
+    }
+
+    /// Breaks an autokey XOR ciphertext, rating each possible plaintext with the given scorer
+    pub fn break_autokey_with<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        mode: Autokey,
+        scorer: &S,
+    ) -> XorResult<Recovered<u8>> {
+        Self::shortlist_keys(0..=u8::MAX, ciphertext, scorer, |input, key| {
+            Self::autokey_decrypt(input, key, mode)
+        })
+    }
+
+    /// XORs an input with the keystream of a linear congruential generator
+    pub fn lcg(input: &[u8], lcg: &Lcg, seed: u32) -> Vec<u8> {
+        input
+            .iter()
+            .zip(lcg.keystream(seed, input.len()))
+            .map(|(x, y)| x ^ y)
+            .collect()
+    }
+
+    /// Breaks a linear congruential generator XOR ciphertext, recovering
+    /// the low `Lcg::seed_bits` bits of the seed (which are all that matter)
+    ///
+    /// Every one of the 2^`seed_bits` seeds is tried, so generators with a large
+    /// shift take a while to break (2^24 seeds for `Lcg::MSVC`, which calls
+    /// for a cheap scorer such as `Printable` with `break_lcg_with`)
+    pub fn break_lcg(ciphertext: &[u8], lcg: &Lcg) -> XorResult<Recovered<u32>> {
+        Self::break_lcg_with(ciphertext, lcg, &English)
+    }
+
+    /// Breaks a linear congruential generator XOR ciphertext,
+    /// rating each possible plaintext with the given scorer
+    pub fn break_lcg_with<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        lcg: &Lcg,
+        scorer: &S,
+    ) -> XorResult<Recovered<u32>> {
+        let seeds = 0..=(u32::MAX >> (u32::BITS - lcg.seed_bits()));
+
+        Self::shortlist_keys(seeds, ciphertext, scorer, |input, seed| {
+            Self::lcg(input, lcg, seed)
+        })
+    }
+
+    /// Finds the most probable key by decrypting the start of the ciphertext
+    /// with every key, and then the whole ciphertext with the best few
+    fn shortlist_keys<K, I, S, F>(
+        keys: I,
+        ciphertext: &[u8],
+        scorer: &S,
+        decrypt: F,
+    ) -> XorResult<Recovered<K>>
+    where
+        K: Copy,
+        I: IntoIterator<Item = K>,
+        S: Scorer + ?Sized,
+        F: Fn(&[u8], K) -> Vec<u8>,
+    {
+        let sample = &ciphertext[..ciphertext.len().min(SAMPLE_LENGTH)];
+
+        // Scores the sample, only keeping the best keys (ties keep their key order)
+        let mut shortlist: Vec<(K, f64)> = Vec::with_capacity(SHORTLIST_LENGTH + 1);
+        for key in keys {
+            let score = scorer.score(&decrypt(sample, key));
+            if shortlist.len() == SHORTLIST_LENGTH && shortlist.last().is_some_and(|x| score <= x.1)
+            {
+                continue;
+            }
+
+            let position = shortlist.partition_point(|x| x.1 >= score);
+            shortlist.insert(position, (key, score));
+            shortlist.truncate(SHORTLIST_LENGTH);
+        }
+
+        // Keeps the first of any equally probable keys
+        let mut best: Option<Recovered<K>> = None;
+        for (key, _) in shortlist {
+            let plaintext = decrypt(ciphertext, key);
+            let score = scorer.score(&plaintext);
+            if best.as_ref().is_none_or(|x| score > x.score) {
+                best = Some(Recovered {
+                    key,
+                    score,
+                    plaintext,
+                });
+            }
+        }
+
+        best.ok_or(XorError::ProbabilityCalc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorer::Printable;
+
+    /// A plaintext every family is tested with
+    const PLAINTEXT: &[u8] = b"The configuration server is located at the usual address, \
+        check in every hour and wait for further instructions";
+
+    #[test]
+    fn incrementing_key() {
+        let ciphertext = Xor::incrementing_key(PLAINTEXT, 0x42, 7);
+        assert_eq!(Xor::incrementing_key(&ciphertext, 0x42, 7), PLAINTEXT);
+
+        let recovered = Xor::break_incrementing_key(&ciphertext).unwrap();
+        assert_eq!(recovered.key, (0x42, 7));
+        assert_eq!(recovered.plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn autokey() {
+        for mode in [Autokey::Plaintext, Autokey::Ciphertext] {
+            let ciphertext = Xor::autokey_encrypt(PLAINTEXT, 0x9C, mode);
+            assert_eq!(Xor::autokey_decrypt(&ciphertext, 0x9C, mode), PLAINTEXT);
+
+            let recovered = Xor::break_autokey(&ciphertext, mode).unwrap();
+            assert_eq!(recovered.key, 0x9C);
+            assert_eq!(recovered.plaintext, PLAINTEXT);
+        }
+    }
+
+    #[test]
+    fn lcg() {
+        let lcg = Lcg {
+            shift: 4,
+            ..Lcg::MSVC
+        };
+        assert_eq!(lcg.seed_bits(), 12);
+        assert_eq!(Lcg::MSVC.keystream(0, 3), [0x26, 0x27, 0xF6]);
+
+        let ciphertext = Xor::lcg(PLAINTEXT, &lcg, 0xDEAD_BEEF);
+        assert_eq!(Xor::lcg(&ciphertext, &lcg, 0xDEAD_BEEF), PLAINTEXT);
+
+        let recovered = Xor::break_lcg(&ciphertext, &lcg).unwrap();
+        assert_eq!(recovered.key, 0xDEAD_BEEF & 0xFFF);
+        assert_eq!(recovered.plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn lcg_with_cheap_scorer() {
+        // Breaking `Lcg::MSVC` itself means trying 2^24 seeds, which is too slow for a unit test
+        assert_eq!(Lcg::MSVC.seed_bits(), 24);
+
+        let lcg = Lcg {
+            shift: 8,
+            ..Lcg::MSVC
+        };
+        let ciphertext = Xor::lcg(PLAINTEXT, &lcg, 0xDEAD_BEEF);
+        let recovered = Xor::break_lcg_with(&ciphertext, &lcg, &Printable::default()).unwrap();
+        assert_eq!(recovered.key, 0xDEAD_BEEF & 0xFFFF);
+        assert_eq!(recovered.plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn lcg_large_shift() {
+        for shift in [24, 31, 32, 40, u32::MAX] {
+            let lcg = Lcg { shift, ..Lcg::MSVC };
+            assert_eq!(lcg.seed_bits(), 32);
+            assert_eq!(lcg.keystream(0xDEAD_BEEF, 64).len(), 64);
+        }
+
+        let lcg = Lcg {
+            shift: 32,
+            ..Lcg::MSVC
+        };
+        assert_eq!(lcg.keystream(0xDEAD_BEEF, 4), [0; 4]);
+    }
+}
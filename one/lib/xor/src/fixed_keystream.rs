@@ -0,0 +1,243 @@
+use crate::errors::*;
+use crate::scorer::{ChiSquared, English, Scorer};
+use crate::xor::Xor;
+
+/// How many preceding plaintext bytes are scored along with
+/// every keystream byte past the shortest ciphertext
+const CONTEXT: usize = 4;
+
+/// Scores a column of sentence-initial bytes, which are mostly capitals,
+/// by swapping the case of its letters before handing it to another scorer
+///
+/// Otherwise a scorer expecting lowercase text always prefers the key
+/// which turns every capital into its lowercase letter
+struct Capitalized<'a, S: ?Sized>(&'a S);
+
+impl<S: Scorer + ?Sized> Scorer for Capitalized<'_, S> {
+    fn score(&self, input: &[u8]) -> f64 {
+        let swapped: Vec<u8> = input
+            .iter()
+            .map(|&x| if x.is_ascii_alphabetic() { x ^ 0x20 } else { x })
+            .collect();
+
+        self.0.score(&swapped)
+    }
+}
+
+/// The result of breaking ciphertexts which share a keystream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeystreamResult {
+    /// The recovered keystream (as long as the longest ciphertext)
+    pub keystream: Vec<u8>,
+    /// The recovered plaintexts
+    pub plaintexts: Vec<Vec<u8>>,
+    /// The length of the shortest ciphertext, the keystream
+    /// is less reliable past this point since fewer bytes are known
+    pub common_length: usize,
+}
+
+impl Xor {
+    /// Breaks a list of ciphertexts encrypted with the same keystream
+    /// (e.g. CTR with a fixed nonce, or a reused one-time pad)
+    ///
+    /// Columns are rated with the `ChiSquared` scorer, since
+    /// words and n-grams don't survive the transposition
+    pub fn break_fixed_keystream(ciphertexts: &[Vec<u8>]) -> XorResult<KeystreamResult> {
+        Self::break_fixed_keystream_with(ciphertexts, &ChiSquared, &English)
+    }
+
+    /// Breaks a list of ciphertexts encrypted with the same keystream, rating each
+    /// column with `scorer` and the plaintext leading up to it with `context`
+    ///
+    /// Truncated to the shortest ciphertext, they're a repeating key XOR ciphertext,
+    /// so every column is single byte XORed, the keystream is then extended a byte
+    /// at a time, scoring each (shorter) column along with the plaintext preceding
+    /// it, bytes which only one ciphertext covers are little more than a guess
+    ///
+    /// The first column is assumed to start sentences, so capitals are preferred there
+    pub fn break_fixed_keystream_with<S: Scorer + ?Sized, C: Scorer + ?Sized>(
+        ciphertexts: &[Vec<u8>],
+        scorer: &S,
+        context: &C,
+    ) -> XorResult<KeystreamResult> {
+        let common_length = ciphertexts.iter().map(Vec::len).min().unwrap_or(0);
+        let max_length = ciphertexts.iter().map(Vec::len).max().unwrap_or(0);
+        if ciphertexts.len() < 2 || common_length == 0 {
+            return Err(XorError::InsufficientData);
+        }
+
+        // Breaks the columns every ciphertext has
+        let truncated: Vec<Vec<u8>> = ciphertexts
+            .iter()
+            .map(|x| x[..common_length].to_vec())
+            .collect();
+        let mut keystream = Self::transpose_blocks(&truncated, common_length)
+            .iter()
+            .enumerate()
+            .map(|(x, column)| {
+                let candidate = if x == 0 {
+                    Self::single_byte_bruteforce_with(column, &Capitalized(scorer))?
+                } else {
+                    Self::single_byte_bruteforce_with(column, scorer)?
+                };
+
+                Ok(candidate.key)
+            })
+            .collect::<XorResult<Vec<u8>>>()?;
+
+        // Extends the keystream past the shortest ciphertext
+        for x in common_length..max_length {
+            let start = x.saturating_sub(CONTEXT);
+            let rows: Vec<&Vec<u8>> = ciphertexts.iter().filter(|y| y.len() > x).collect();
+
+            // Decrypts the plaintext preceding the column
+            let preceding: Vec<Vec<u8>> = rows
+                .iter()
+                .map(|row| {
+                    row[start..x]
+                        .iter()
+                        .zip(&keystream[start..x])
+                        .map(|(y, z)| y ^ z)
+                        .collect()
+                })
+                .collect();
+
+            let mut best: Option<(u8, f64)> = None;
+            for key in 0..=u8::MAX {
+                let column: Vec<u8> = rows.iter().map(|row| row[x] ^ key).collect();
+                let fit: f64 = preceding
+                    .iter()
+                    .zip(&column)
+                    .map(|(plaintext, &byte)| context.score(&[&plaintext[..], &[byte]].concat()))
+                    .sum();
+                let score = scorer.score(&column) + fit / rows.len() as f64;
+
+                // Keeps the first of any equally probable keys
+                if best.is_none_or(|(_, best)| score > best) {
+                    best = Some((key, score));
+                }
+            }
+
+            keystream.push(best.ok_or(XorError::ProbabilityCalc)?.0);
+        }
+
+        let plaintexts = ciphertexts
+            .iter()
+            .map(|x| x.iter().zip(&keystream).map(|(y, z)| y ^ z).collect())
+            .collect();
+
+        Ok(KeystreamResult {
+            keystream,
+            plaintexts,
+            common_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plaintexts which were encrypted with the same keystream
+    const PLAINTEXTS: [&[u8]; 20] = [
+        b"I have met them at close of day",
+        b"Coming with vivid faces",
+        b"From counter or desk among grey",
+        b"Eighteenth-century houses.",
+        b"I have passed with a nod of the head",
+        b"Or polite meaningless words,",
+        b"Or have lingered awhile and said",
+        b"Polite meaningless words,",
+        b"And thought before I had done",
+        b"Of a mocking tale or a gibe",
+        b"To please a companion",
+        b"Around the fire at the club,",
+        b"Being certain that they and I",
+        b"But lived where motley is worn:",
+        b"All changed, changed utterly:",
+        b"A terrible beauty is born.",
+        b"That woman's days were spent",
+        b"In ignorant good will,",
+        b"Her nights in argument",
+        b"Until her voice grew shrill.",
+    ];
+
+    /// A (not so) random keystream
+    fn keystream() -> Vec<u8> {
+        (0..64u8)
+            .map(|x| x.wrapping_mul(151).rotate_left(3) ^ 0xA7)
+            .collect()
+    }
+
+    #[test]
+    fn break_fixed_keystream() {
+        let keystream = keystream();
+        let ciphertexts: Vec<Vec<u8>> = PLAINTEXTS
+            .iter()
+            .map(|x| x.iter().zip(&keystream).map(|(y, z)| y ^ z).collect())
+            .collect();
+
+        let result = Xor::break_fixed_keystream(&ciphertexts).unwrap();
+        assert_eq!(result.common_length, 21);
+        assert_eq!(result.keystream.len(), 36);
+        assert_eq!(result.plaintexts.len(), PLAINTEXTS.len());
+
+        // Every byte at least two ciphertexts cover is recovered (including those
+        // past the shortest ciphertext), the rest of the longest one is left to chance
+        assert_eq!(result.keystream[..21], keystream[..21]);
+        assert_eq!(result.keystream[21..32], keystream[21..32]);
+        for (plaintext, expected) in result.plaintexts.iter().zip(PLAINTEXTS) {
+            let length = expected.len().min(32);
+            assert_eq!(plaintext[..length], expected[..length]);
+        }
+        assert_eq!(result.plaintexts[14], b"All changed, changed utterly:");
+    }
+
+    /// Scores an input by whether it appears in any of the plaintexts,
+    /// a stand-in for a scorer which knows its domain better than `English`
+    struct Corpus;
+
+    impl Scorer for Corpus {
+        fn score(&self, input: &[u8]) -> f64 {
+            let found = PLAINTEXTS
+                .iter()
+                .any(|x| x.windows(input.len()).any(|y| y == input));
+            if found {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    #[test]
+    fn break_with_context_scorer() {
+        let keystream = keystream();
+        let ciphertexts: Vec<Vec<u8>> = PLAINTEXTS
+            .iter()
+            .map(|x| x.iter().zip(&keystream).map(|(y, z)| y ^ z).collect())
+            .collect();
+
+        // The context settles even the bytes only the longest ciphertext covers
+        let result = Xor::break_fixed_keystream_with(&ciphertexts, &ChiSquared, &Corpus).unwrap();
+        assert_eq!(result.common_length, 21);
+        assert_eq!(result.keystream, keystream[..36]);
+        assert_eq!(result.plaintexts, PLAINTEXTS);
+    }
+
+    #[test]
+    fn insufficient_data() {
+        assert!(matches!(
+            Xor::break_fixed_keystream(&[]),
+            Err(XorError::InsufficientData)
+        ));
+        assert!(matches!(
+            Xor::break_fixed_keystream(&[b"abc".to_vec()]),
+            Err(XorError::InsufficientData)
+        ));
+        assert!(matches!(
+            Xor::break_fixed_keystream(&[b"abc".to_vec(), vec![]]),
+            Err(XorError::InsufficientData)
+        ));
+    }
+}
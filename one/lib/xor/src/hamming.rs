@@ -0,0 +1,163 @@
+use crate::errors::*;
+use crate::xor::Xor;
+
+/// The number of bytes in a word
+const WORD: usize = 8;
+
+impl Xor {
+    /// Calculates the hamming distance between two byte slices of equal length
+    ///
+    /// The slices are compared a word (8 bytes) at a time, with
+    /// the `simd` feature AVX2 is used when the CPU supports it
+    pub fn hamming_distance_bytes(lvalue: &[u8], rvalue: &[u8]) -> XorResult<u32> {
+        // Checks the slice lengths
+        if lvalue.len() != rvalue.len() {
+            return Err(XorError::LengthNotEqual);
+        }
+
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU was just checked to support AVX2
+            return Ok(unsafe { avx2_distance(lvalue, rvalue) });
+        }
+
+        Ok(word_distance(lvalue, rvalue))
+    }
+
+    /// Calculates the hamming distance between every pair of blocks
+    ///
+    /// The result is a symmetric matrix with a zeroed diagonal,
+    /// where `matrix[x][y]` is the distance between blocks `x` and `y`
+    pub fn hamming_matrix<T: AsRef<[u8]>>(blocks: &[T]) -> XorResult<Vec<Vec<u32>>> {
+        let mut matrix: Vec<Vec<u32>> = vec![vec![0; blocks.len()]; blocks.len()];
+        for x in 0..blocks.len() {
+            for y in x + 1..blocks.len() {
+                let distance =
+                    Self::hamming_distance_bytes(blocks[x].as_ref(), blocks[y].as_ref())?;
+                matrix[x][y] = distance;
+                matrix[y][x] = distance;
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Calculates the average hamming distance between every pair of blocks
+    pub fn mean_pairwise_hamming<T: AsRef<[u8]>>(blocks: &[T]) -> XorResult<f64> {
+        if blocks.len() < 2 {
+            return Err(XorError::InsufficientData);
+        }
+
+        let mut distance: u64 = 0;
+        for x in 0..blocks.len() {
+            for y in x + 1..blocks.len() {
+                distance += u64::from(Self::hamming_distance_bytes(
+                    blocks[x].as_ref(),
+                    blocks[y].as_ref(),
+                )?);
+            }
+        }
+
+        let n_pairs = blocks.len() * (blocks.len() - 1) / 2;
+        Ok(distance as f64 / n_pairs as f64)
+    }
+}
+
+/// Counts the differing bits of two equally long slices a word at a time
+///
+/// This is always inlined, so that it's compiled with AVX2 inside `avx2_distance`
+#[inline(always)]
+fn word_distance(lvalue: &[u8], rvalue: &[u8]) -> u32 {
+    let lwords = lvalue.chunks_exact(WORD);
+    let rwords = rvalue.chunks_exact(WORD);
+
+    // The bytes which don't fill up a word are compared one by one
+    let remainder: u32 = lwords
+        .remainder()
+        .iter()
+        .zip(rwords.remainder())
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum();
+
+    lwords
+        .zip(rwords)
+        .map(|(x, y)| {
+            let x = u64::from_ne_bytes(x.try_into().expect("Words are 8 bytes long!"));
+            let y = u64::from_ne_bytes(y.try_into().expect("Words are 8 bytes long!"));
+            (x ^ y).count_ones()
+        })
+        .sum::<u32>()
+        + remainder
+}
+
+/// Counts the differing bits of two equally long slices with AVX2,
+/// which lets the compiler vectorize the population count
+/// (see `benches/hamming.rs`, it's over twice as fast)
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+fn avx2_distance(lvalue: &[u8], rvalue: &[u8]) -> u32 {
+    word_distance(lvalue, rvalue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn hamming_distance_bytes() {
+        assert_eq!(
+            Xor::hamming_distance_bytes(b"this is a test", b"wokka wokka!!!").unwrap(),
+            37
+        );
+        assert_eq!(Xor::hamming_distance_bytes(&[], &[]).unwrap(), 0);
+        assert!(matches!(
+            Xor::hamming_distance_bytes(b"ab", b"abc"),
+            Err(XorError::LengthNotEqual)
+        ));
+    }
+
+    #[test]
+    fn matches_bitvec_distance() {
+        // Covers whole words, partial words and both combined
+        let lvalue: Vec<u8> = (0..=u8::MAX).collect();
+        let rvalue: Vec<u8> = (0..=u8::MAX).map(|x| x.wrapping_mul(31) ^ 0x5A).collect();
+
+        for length in [1, 7, 8, 9, 63, 64, 65, 256] {
+            let expected = Xor::hamming_distance(
+                &BitVec::from_slice(&lvalue[..length]),
+                &BitVec::from_slice(&rvalue[..length]),
+            )
+            .unwrap();
+
+            assert_eq!(
+                Xor::hamming_distance_bytes(&lvalue[..length], &rvalue[..length]).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn hamming_matrix() {
+        let blocks: [&[u8]; 3] = [b"abc", b"abd", b"xyz"];
+
+        let matrix = Xor::hamming_matrix(&blocks).unwrap();
+        for (x, row) in matrix.iter().enumerate() {
+            assert_eq!(row[x], 0);
+            for (y, &distance) in row.iter().enumerate() {
+                assert_eq!(distance, matrix[y][x]);
+            }
+        }
+        assert_eq!(matrix[0][1], 3);
+
+        let mean = Xor::mean_pairwise_hamming(&blocks).unwrap();
+        assert_eq!(
+            mean,
+            f64::from(matrix[0][1] + matrix[0][2] + matrix[1][2]) / 3.0
+        );
+        assert!(matches!(
+            Xor::mean_pairwise_hamming(&blocks[..1]),
+            Err(XorError::InsufficientData)
+        ));
+    }
+}
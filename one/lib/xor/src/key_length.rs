@@ -0,0 +1,249 @@
+use crate::errors::*;
+use crate::xor::Xor;
+use std::collections::HashMap;
+
+/// The most blocks compared pairwise by the Hamming estimator
+const MAX_HAMMING_BLOCKS: usize = 64;
+
+/// The ways a repeating key's length can be estimated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyLengthEstimator {
+    /// The normalized Hamming distance between every pair of blocks
+    Hamming,
+    /// The index of coincidence of the transposed columns
+    IndexOfCoincidence,
+    /// The spacing between repeated trigrams (Kasiski examination)
+    Kasiski,
+}
+
+impl KeyLengthEstimator {
+    /// Every estimator
+    pub const ALL: [Self; 3] = [Self::Hamming, Self::IndexOfCoincidence, Self::Kasiski];
+}
+
+/// A possible key length
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyLength {
+    /// The key length
+    pub length: usize,
+    /// How likely the key length is (between 0 and 1)
+    pub score: f64,
+}
+
+impl Xor {
+    /// Ranks every key length up to (and including) `max_length` with
+    /// a given estimator, from the most to the least probable
+    ///
+    /// Lengths which the ciphertext is too short to judge are left out,
+    /// a ciphertext shorter than two bytes can't be judged at all
+    pub fn rank_key_lengths(
+        ciphertext: &[u8],
+        max_length: usize,
+        estimator: KeyLengthEstimator,
+    ) -> XorResult<Vec<KeyLength>> {
+        if max_length == 0 {
+            return Err(XorError::InvalidKeyLength);
+        }
+        if ciphertext.len() < 2 {
+            return Err(XorError::InsufficientData);
+        }
+
+        let mut ranking: Vec<KeyLength> = vec![];
+        for length in 1..=max_length {
+            let score = match estimator {
+                KeyLengthEstimator::Hamming => Self::hamming_score(ciphertext, length)?,
+                KeyLengthEstimator::IndexOfCoincidence => {
+                    Self::coincidence_score(ciphertext, length)
+                }
+                KeyLengthEstimator::Kasiski => Self::kasiski_score(ciphertext, length),
+            };
+
+            if let Some(score) = score {
+                ranking.push(KeyLength { length, score });
+            }
+        }
+
+        Self::sort_key_lengths(&mut ranking);
+        Ok(ranking)
+    }
+
+    /// Ranks every key length up to (and including) `max_length` by
+    /// combining several estimators, from the most to the least probable
+    ///
+    /// Each estimator's scores are scaled so that its best length scores 1,
+    /// and the scaled scores are then averaged
+    pub fn rank_key_lengths_combined(
+        ciphertext: &[u8],
+        max_length: usize,
+        estimators: &[KeyLengthEstimator],
+    ) -> XorResult<Vec<KeyLength>> {
+        let mut combined: HashMap<usize, f64> = HashMap::new();
+        for &estimator in estimators {
+            let ranking = Self::rank_key_lengths(ciphertext, max_length, estimator)?;
+            let best = ranking.first().map_or(0.0, |x| x.score);
+            if best <= 0.0 {
+                continue;
+            }
+
+            for key_length in ranking {
+                *combined.entry(key_length.length).or_insert(0.0) +=
+                    key_length.score / best / estimators.len() as f64;
+            }
+        }
+
+        let mut ranking: Vec<KeyLength> = combined
+            .into_iter()
+            .map(|(length, score)| KeyLength { length, score })
+            .collect();
+
+        Self::sort_key_lengths(&mut ranking);
+        Ok(ranking)
+    }
+
+    /// Sorts key lengths by descending score, preferring shorter lengths on ties
+    fn sort_key_lengths(ranking: &mut [KeyLength]) {
+        ranking.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.length.cmp(&b.length)));
+    }
+
+    /// Scores a key length by the average normalized Hamming distance between
+    /// every pair of (the first few) blocks, XORing two blocks encrypted with
+    /// the same key cancels it out, which leaves two similar plaintexts
+    fn hamming_score(ciphertext: &[u8], length: usize) -> XorResult<Option<f64>> {
+        let blocks: Vec<&[u8]> = ciphertext
+            .chunks_exact(length)
+            .take(MAX_HAMMING_BLOCKS)
+            .collect();
+        if blocks.len() < 2 {
+            return Ok(None);
+        }
+
+        // Normalizes the distance to bits per byte
+        let distance = Self::mean_pairwise_hamming(&blocks)? / length as f64;
+        Ok(Some(1.0 - distance / 8.0))
+    }
+
+    /// Scores a key length by the average index of coincidence of
+    /// the transposed columns, which is high for single byte XORed text
+    /// and close to 1/256 for random bytes
+    fn coincidence_score(ciphertext: &[u8], length: usize) -> Option<f64> {
+        if ciphertext.len() < length * 2 {
+            return None;
+        }
+
+        let blocks = Self::block_ciphertext(ciphertext, length);
+        let columns = Self::transpose_blocks(&blocks, length);
+
+        let mut total = 0.0;
+        for column in &columns {
+            let mut counts = [0u64; 256];
+            for &byte in column {
+                counts[byte as usize] += 1;
+            }
+
+            let n = column.len() as f64;
+            let coincidences: u64 = counts.iter().map(|x| x * x.saturating_sub(1)).sum();
+            total += coincidences as f64 / (n * (n - 1.0));
+        }
+
+        Some(total / length as f64)
+    }
+
+    /// Scores a key length by the share of distances between repeated trigrams
+    /// that it divides, above the share expected by chance (1 in `length`)
+    ///
+    /// A key length of 1 divides everything, so it always scores 0
+    fn kasiski_score(ciphertext: &[u8], length: usize) -> Option<f64> {
+        let mut positions: HashMap<&[u8], usize> = HashMap::new();
+        let mut distances: Vec<usize> = vec![];
+        for (x, trigram) in ciphertext.windows(3).enumerate() {
+            if let Some(previous) = positions.insert(trigram, x) {
+                distances.push(x - previous);
+            }
+        }
+
+        if distances.is_empty() || length > ciphertext.len() / 2 {
+            return None;
+        }
+        if length == 1 {
+            return Some(0.0);
+        }
+
+        let n_divisible = distances
+            .iter()
+            .filter(|x| x.is_multiple_of(length))
+            .count();
+        let share = n_divisible as f64 / distances.len() as f64;
+        let chance = 1.0 / length as f64;
+
+        Some(((share - chance) / (1.0 - chance)).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plaintext long enough for every estimator
+    const PLAINTEXT: &[u8] = b"Now that the party is jumping, with the bass kicked in and the \
+        vega's are pumpin'. Quick to the point, to the point, no faking, cooking MC's like a \
+        pound of bacon. Burning 'em, if you ain't quick and nimble, I go crazy when I hear a \
+        cymbal and a hi-hat with a souped up tempo. I'm on a roll, it's time to go solo.";
+
+    /// Encrypts the plaintext with a repeating key
+    fn encrypt(key: &[u8]) -> Vec<u8> {
+        PLAINTEXT
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(x, y)| x ^ y)
+            .collect()
+    }
+
+    #[test]
+    fn estimators_rank_the_key_length() {
+        let ciphertext = encrypt(b"Vanilla");
+
+        for estimator in KeyLengthEstimator::ALL {
+            let ranking = Xor::rank_key_lengths(&ciphertext, 20, estimator).unwrap();
+            let top: Vec<usize> = ranking.iter().take(3).map(|x| x.length).collect();
+
+            // Multiples of the key length are just as plausible
+            assert!(
+                top.iter().any(|x| x % 7 == 0),
+                "{estimator:?} ranked {top:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn combined_ranking() {
+        let ciphertext = encrypt(b"Vanilla");
+
+        let ranking =
+            Xor::rank_key_lengths_combined(&ciphertext, 20, &KeyLengthEstimator::ALL).unwrap();
+        assert_eq!(ranking[0].length % 7, 0);
+        assert!(ranking.windows(2).all(|x| x[0].score >= x[1].score));
+    }
+
+    #[test]
+    fn short_ciphertexts_are_skipped() {
+        let ranking = Xor::rank_key_lengths(b"abcdef", 10, KeyLengthEstimator::Hamming).unwrap();
+        let lengths: Vec<usize> = ranking.iter().map(|x| x.length).collect();
+
+        assert_eq!(lengths.len(), 3);
+        assert!(lengths.iter().all(|&x| x <= 3));
+    }
+
+    #[test]
+    fn invalid_rankings() {
+        for estimator in KeyLengthEstimator::ALL {
+            assert!(matches!(
+                Xor::rank_key_lengths(b"a", 10, estimator),
+                Err(XorError::InsufficientData)
+            ));
+            assert!(matches!(
+                Xor::rank_key_lengths(b"abcdef", 0, estimator),
+                Err(XorError::InvalidKeyLength)
+            ));
+        }
+    }
+}
@@ -1,5 +1,24 @@
+mod breaker;
+mod crib;
+mod detect;
 mod errors;
+mod families;
+mod fixed_keystream;
+mod hamming;
+mod key_length;
+mod model;
+mod scorer;
+mod stream;
 mod xor;
 
-pub use errors::XorResult;
-pub use xor::Xor;
+pub use breaker::{BreakOptions, BreakResult};
+pub use crib::{CribMatch, PartialKeystream};
+pub use detect::Detection;
+pub use errors::{XorError, XorResult};
+pub use families::{Autokey, Lcg, Recovered};
+pub use fixed_keystream::KeystreamResult;
+pub use key_length::{KeyLength, KeyLengthEstimator};
+pub use model::LanguageModel;
+pub use scorer::{ChiSquared, English, Fragment, NGrams, Printable, Scorer, Weighted, WordList};
+pub use stream::{XorIterator, XorStream};
+pub use xor::{Candidate, Xor};
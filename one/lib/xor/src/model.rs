@@ -0,0 +1,341 @@
+use crate::errors::*;
+use crate::scorer::Scorer;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// The bytes every serialized model starts with
+const MAGIC: &[u8; 4] = b"XLM\x01";
+
+/// The longest supported n-gram
+const MAX_ORDER: usize = 8;
+
+/// The weight applied every time a lookup backs off to a shorter context
+const BACKOFF: f64 = 0.4;
+
+/// A byte-level n-gram language model trained from a sample corpus
+///
+/// Probabilities are estimated with stupid backoff, falling back
+/// to add-one smoothed unigrams, so any input (text or binary) can be scored
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageModel {
+    /// The longest n-gram the model keeps track of
+    order: usize,
+    /// The cross-entropy (in bits per byte) of the training corpus, this
+    /// is what a "perfect" input is expected to look like
+    reference_entropy: f64,
+    /// The n-gram counts, indexed by n-gram length minus one
+    counts: Vec<HashMap<Vec<u8>, u32>>,
+    /// How often each context is followed by any byte, indexed by context length
+    context_totals: Vec<HashMap<Vec<u8>, u32>>,
+}
+
+impl LanguageModel {
+    /// Trains a model of a given order (1 for unigrams, 2 for bigrams, ...)
+    /// from a sample corpus, which mustn't be empty
+    pub fn train(corpus: &[u8], order: usize) -> XorResult<Self> {
+        if !(1..=MAX_ORDER).contains(&order) {
+            return Err(XorError::InvalidModel);
+        }
+
+        if corpus.is_empty() {
+            return Err(XorError::InsufficientData);
+        }
+
+        let mut counts: Vec<HashMap<Vec<u8>, u32>> = vec![HashMap::new(); order];
+        for (n, table) in counts.iter_mut().enumerate() {
+            for ngram in corpus.windows(n + 1) {
+                match table.get_mut(ngram) {
+                    Some(count) => *count += 1,
+                    None => {
+                        table.insert(ngram.to_vec(), 1);
+                    }
+                }
+            }
+        }
+
+        let mut model = Self::from_counts(order, 0.0, counts);
+        model.reference_entropy = model.cross_entropy(corpus);
+
+        // A model without a reference would score every input the same
+        if !model.reference_entropy.is_finite() {
+            return Err(XorError::InsufficientData);
+        }
+
+        Ok(model)
+    }
+
+    /// Trains a model of a given order from the contents of a file
+    pub fn train_file<P: AsRef<Path>>(path: P, order: usize) -> XorResult<Self> {
+        Self::train(&fs::read(path)?, order)
+    }
+
+    /// Builds a model from its n-gram counts
+    fn from_counts(
+        order: usize,
+        reference_entropy: f64,
+        counts: Vec<HashMap<Vec<u8>, u32>>,
+    ) -> Self {
+        // Every n-gram adds to the total of the context preceding its last byte
+        let mut context_totals: Vec<HashMap<Vec<u8>, u32>> = vec![HashMap::new(); order];
+        for (n, table) in counts.iter().enumerate() {
+            for (ngram, count) in table {
+                *context_totals[n].entry(ngram[..n].to_vec()).or_insert(0) += count;
+            }
+        }
+
+        Self {
+            order,
+            reference_entropy,
+            counts,
+            context_totals,
+        }
+    }
+
+    /// The longest n-gram the model keeps track of
+    pub const fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Estimates the probability of `byte` following a given context
+    fn probability(&self, context: &[u8], byte: u8) -> f64 {
+        let mut weight = 1.0;
+        for n in (1..=context.len().min(self.order - 1)).rev() {
+            let context = &context[context.len() - n..];
+            let total = self.context_totals[n].get(context).copied().unwrap_or(0);
+
+            let mut ngram = context.to_vec();
+            ngram.push(byte);
+            if let Some(&count) = self.counts[n].get(&ngram) {
+                return weight * f64::from(count) / f64::from(total);
+            }
+
+            weight *= BACKOFF;
+        }
+
+        // Unigrams are smoothed, so that unseen bytes are merely unlikely
+        let count = self.counts[0].get(&[byte][..]).copied().unwrap_or(0);
+        let total = self.context_totals[0].get(&[][..]).copied().unwrap_or(0);
+        weight * f64::from(count + 1) / f64::from(total + 256)
+    }
+
+    /// Calculates the cross-entropy (in bits per byte) of an input under the model
+    ///
+    /// The lower it is, the more the input resembles the training corpus
+    pub fn cross_entropy(&self, input: &[u8]) -> f64 {
+        if input.is_empty() {
+            return f64::INFINITY;
+        }
+
+        let bits: f64 = (0..input.len())
+            .map(|x| {
+                let context = &input[x.saturating_sub(self.order - 1)..x];
+                -self.probability(context, input[x]).log2()
+            })
+            .sum();
+
+        bits / input.len() as f64
+    }
+
+    /// Serializes the model into a compact binary format
+    ///
+    /// This is the magic bytes, the order, the reference entropy, and then
+    /// every table as a varint entry count followed by sorted `(n-gram, varint count)` pairs
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = MAGIC.to_vec();
+        output.push(self.order as u8);
+        output.extend_from_slice(&self.reference_entropy.to_le_bytes());
+
+        for table in &self.counts {
+            let mut entries: Vec<(&Vec<u8>, &u32)> = table.iter().collect();
+            entries.sort_unstable();
+
+            write_varint(&mut output, entries.len() as u64);
+            for (ngram, &count) in entries {
+                output.extend_from_slice(ngram);
+                write_varint(&mut output, u64::from(count));
+            }
+        }
+
+        output
+    }
+
+    /// Deserializes a model produced by `to_bytes`
+    pub fn from_bytes(input: &[u8]) -> XorResult<Self> {
+        let mut input = input
+            .strip_prefix(&MAGIC[..])
+            .ok_or(XorError::InvalidModel)?;
+
+        let order = *take(&mut input, 1)?.first().ok_or(XorError::InvalidModel)? as usize;
+        if !(1..=MAX_ORDER).contains(&order) {
+            return Err(XorError::InvalidModel);
+        }
+
+        let reference_entropy = f64::from_le_bytes(
+            take(&mut input, 8)?
+                .try_into()
+                .map_err(|_| XorError::InvalidModel)?,
+        );
+        if !reference_entropy.is_finite() {
+            return Err(XorError::InvalidModel);
+        }
+
+        let mut counts: Vec<HashMap<Vec<u8>, u32>> = vec![HashMap::new(); order];
+        for (n, table) in counts.iter_mut().enumerate() {
+            let n_entries = read_varint(&mut input)?;
+            for _ in 0..n_entries {
+                let ngram = take(&mut input, n + 1)?.to_vec();
+                let count: u32 = read_varint(&mut input)?
+                    .try_into()
+                    .map_err(|_| XorError::InvalidModel)?;
+                if count == 0 {
+                    return Err(XorError::InvalidModel);
+                }
+                table.insert(ngram, count);
+            }
+        }
+
+        if !input.is_empty() {
+            return Err(XorError::InvalidModel);
+        }
+
+        Ok(Self::from_counts(order, reference_entropy, counts))
+    }
+
+    /// Saves the model to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> XorResult<()> {
+        Ok(fs::write(path, self.to_bytes())?)
+    }
+
+    /// Loads a model from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> XorResult<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Scorer for LanguageModel {
+    /// Compares the input's cross-entropy to the training corpus',
+    /// anything at least as predictable as the corpus scores 1
+    fn score(&self, input: &[u8]) -> f64 {
+        if input.is_empty() {
+            return 0.0;
+        }
+
+        (self.reference_entropy - self.cross_entropy(input))
+            .exp2()
+            .min(1.0)
+    }
+}
+
+/// Appends an unsigned LEB128 varint
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// Reads an unsigned LEB128 varint, advancing the input
+fn read_varint(input: &mut &[u8]) -> XorResult<u64> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *take(input, 1)?.first().ok_or(XorError::InvalidModel)?;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(XorError::InvalidModel)
+}
+
+/// Takes a given number of bytes from the input, advancing it
+fn take<'a>(input: &mut &'a [u8], length: usize) -> XorResult<&'a [u8]> {
+    if input.len() < length {
+        return Err(XorError::InvalidModel);
+    }
+
+    let (taken, rest) = input.split_at(length);
+    *input = rest;
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xor::Xor;
+
+    /// A small English corpus
+    const CORPUS: &[u8] = b"It was the best of times, it was the worst of times, it was the age \
+        of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+        of incredulity, it was the season of Light, it was the season of Darkness, it was the \
+        spring of hope, it was the winter of despair, we had everything before us, we had \
+        nothing before us, we were all going direct to Heaven, we were all going direct the \
+        other way";
+
+    #[test]
+    fn invalid_order() {
+        assert!(matches!(
+            LanguageModel::train(CORPUS, 0),
+            Err(XorError::InvalidModel)
+        ));
+        assert!(matches!(
+            LanguageModel::train(CORPUS, MAX_ORDER + 1),
+            Err(XorError::InvalidModel)
+        ));
+    }
+
+    #[test]
+    fn empty_corpus() {
+        assert!(matches!(
+            LanguageModel::train(b"", 3),
+            Err(XorError::InsufficientData)
+        ));
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let model = LanguageModel::train(CORPUS, 3).unwrap();
+        let bytes = model.to_bytes();
+
+        assert_eq!(LanguageModel::from_bytes(&bytes).unwrap(), model);
+    }
+
+    #[test]
+    fn malformed_models() {
+        let bytes = LanguageModel::train(CORPUS, 2).unwrap().to_bytes();
+
+        assert!(LanguageModel::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(LanguageModel::from_bytes(&bytes[1..]).is_err());
+        assert!(LanguageModel::from_bytes(&[&bytes[..], &[0]].concat()).is_err());
+
+        // The reference entropy must be finite
+        let mut infinite = bytes.clone();
+        infinite[5..13].copy_from_slice(&f64::INFINITY.to_le_bytes());
+        assert!(LanguageModel::from_bytes(&infinite).is_err());
+    }
+
+    #[test]
+    fn scores_similar_text_higher() {
+        let model = LanguageModel::train(CORPUS, 3).unwrap();
+
+        let similar = model.score(b"it was the season of hope");
+        let garbage = model.score(b"\x8f\x12zq\x00\x99kx\xfe");
+        assert!(similar > garbage);
+        assert!((0.0..=1.0).contains(&similar));
+        assert!(model.cross_entropy(b"it was") < model.cross_entropy(b"xq zv"));
+    }
+
+    #[test]
+    fn bruteforce_with_model() {
+        let model = LanguageModel::train(CORPUS, 3).unwrap();
+
+        let ciphertext: Vec<u8> = b"we had everything before us"
+            .iter()
+            .map(|x| x ^ 0x5A)
+            .collect();
+        let candidate = Xor::single_byte_bruteforce_with(&ciphertext, &model).unwrap();
+        assert_eq!(candidate.plaintext, b"we had everything before us");
+    }
+}
@@ -0,0 +1,497 @@
+/// Rates how likely a given input is to be a plaintext
+///
+/// Scores range from 0 (garbage) to 1 (almost certainly a plaintext),
+/// which keeps different scorers comparable and combinable
+pub trait Scorer {
+    /// Scores a given input
+    fn score(&self, input: &[u8]) -> f64;
+}
+
+/// Relative frequency of letters (and spaces) in the English language
+const ENGLISH_FREQUENCIES: [f64; 27] = [
+    0.0651738, 0.0124248, 0.0217339, 0.0349835, 0.1041442, 0.0197881, 0.0158610, 0.0492888,
+    0.0558094, 0.0009033, 0.0050529, 0.0331490, 0.0202124, 0.0564513, 0.0596302, 0.0137645,
+    0.0008606, 0.0497563, 0.0515760, 0.0729357, 0.0225134, 0.0082903, 0.0171272, 0.0013692,
+    0.0145984, 0.0007836, 0.1918182,
+];
+
+/// Relative frequency of the most common English bigrams
+/// (sorted, so that they can be binary searched)
+const ENGLISH_BIGRAMS: [(&[u8; 2], f64); 50] = [
+    (b"AL", 0.0109),
+    (b"AN", 0.0199),
+    (b"AR", 0.0107),
+    (b"AS", 0.0087),
+    (b"AT", 0.0149),
+    (b"BE", 0.0058),
+    (b"CE", 0.0065),
+    (b"CH", 0.0060),
+    (b"CO", 0.0079),
+    (b"DE", 0.0076),
+    (b"EA", 0.0069),
+    (b"ED", 0.0117),
+    (b"EN", 0.0145),
+    (b"ER", 0.0205),
+    (b"ES", 0.0134),
+    (b"HA", 0.0093),
+    (b"HE", 0.0307),
+    (b"HI", 0.0076),
+    (b"IC", 0.0070),
+    (b"IN", 0.0243),
+    (b"IO", 0.0083),
+    (b"IS", 0.0113),
+    (b"IT", 0.0112),
+    (b"LE", 0.0083),
+    (b"LI", 0.0062),
+    (b"LL", 0.0058),
+    (b"MA", 0.0057),
+    (b"ME", 0.0079),
+    (b"ND", 0.0135),
+    (b"NE", 0.0069),
+    (b"NG", 0.0095),
+    (b"NT", 0.0104),
+    (b"OF", 0.0117),
+    (b"OM", 0.0055),
+    (b"ON", 0.0176),
+    (b"OR", 0.0128),
+    (b"OU", 0.0087),
+    (b"RA", 0.0069),
+    (b"RE", 0.0185),
+    (b"RI", 0.0073),
+    (b"RO", 0.0073),
+    (b"SE", 0.0093),
+    (b"SI", 0.0055),
+    (b"ST", 0.0105),
+    (b"TE", 0.0120),
+    (b"TH", 0.0356),
+    (b"TI", 0.0134),
+    (b"TO", 0.0104),
+    (b"UR", 0.0054),
+    (b"VE", 0.0083),
+];
+
+/// Relative frequency of the most common English trigrams
+/// (sorted, so that they can be binary searched)
+const ENGLISH_TRIGRAMS: [(&[u8; 3], f64); 30] = [
+    (b"ALL", 0.0025),
+    (b"AND", 0.0073),
+    (b"ATE", 0.0025),
+    (b"ATI", 0.0026),
+    (b"ENT", 0.0042),
+    (b"ERE", 0.0031),
+    (b"ERS", 0.0028),
+    (b"EST", 0.0028),
+    (b"ETH", 0.0024),
+    (b"FOR", 0.0034),
+    (b"FTH", 0.0021),
+    (b"HAT", 0.0026),
+    (b"HER", 0.0036),
+    (b"HES", 0.0024),
+    (b"HIS", 0.0024),
+    (b"ING", 0.0072),
+    (b"INT", 0.0032),
+    (b"ION", 0.0042),
+    (b"ITH", 0.0021),
+    (b"NTH", 0.0033),
+    (b"OFT", 0.0022),
+    (b"ONT", 0.0020),
+    (b"OTH", 0.0021),
+    (b"RES", 0.0021),
+    (b"STH", 0.0021),
+    (b"TER", 0.0030),
+    (b"THA", 0.0033),
+    (b"THE", 0.0181),
+    (b"TIO", 0.0031),
+    (b"VER", 0.0024),
+];
+
+/// The most common English words (sorted, so that they can be binary searched)
+const ENGLISH_WORDS: [&str; 100] = [
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "back", "be",
+    "because", "but", "by", "can", "come", "could", "day", "do", "even", "first", "for", "from",
+    "get", "give", "go", "good", "have", "he", "her", "him", "his", "how", "i", "if", "in", "into",
+    "is", "it", "its", "just", "know", "like", "look", "make", "me", "most", "my", "new", "no",
+    "not", "now", "of", "on", "one", "only", "or", "other", "our", "out", "over", "people", "say",
+    "see", "she", "so", "some", "take", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "think", "this", "time", "to", "two", "up", "us", "use", "want", "way", "we",
+    "well", "what", "when", "which", "who", "will", "with", "work", "would", "you",
+];
+
+/// Whether or not a byte is printable ASCII (including common whitespace)
+const fn is_printable(byte: u8) -> bool {
+    matches!(byte, b' '..=b'~' | b'\n' | b'\r' | b'\t')
+}
+
+/// Scores an input by how closely its letter, capital, digit, punctuation,
+/// and non-printable byte counts match English (Pearson's chi-squared)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ChiSquared;
+
+impl ChiSquared {
+    /// The expected share of capital letters (out of every letter)
+    const CAPITALS: f64 = 0.03;
+    /// The expected share of digits
+    const DIGITS: f64 = 0.005;
+    /// The expected share of punctuation (and whitespace other than spaces)
+    const PUNCTUATION: f64 = 0.02;
+    /// The expected share of non-printable bytes
+    const NON_PRINTABLE: f64 = 0.0001;
+
+    /// Calculates the chi-squared statistic of a given input
+    pub fn statistic(input: &[u8]) -> f64 {
+        // Lowercase letters and spaces, followed by capitals,
+        // digits, punctuation, and non-printable bytes
+        let mut counts = [0f64; 31];
+        for &byte in input {
+            let bucket = match byte {
+                b'a'..=b'z' => (byte - b'a') as usize,
+                b' ' => 26,
+                b'A'..=b'Z' => 27,
+                b'0'..=b'9' => 28,
+                x if is_printable(x) => 29,
+                _ => 30,
+            };
+            counts[bucket] += 1.0;
+        }
+
+        let total = input.len() as f64;
+        let english_share = 1.0 - Self::DIGITS - Self::PUNCTUATION - Self::NON_PRINTABLE;
+        let letter_share: f64 = ENGLISH_FREQUENCIES[..26].iter().sum::<f64>() * english_share;
+
+        let mut statistic = 0.0;
+        for (x, count) in counts.iter().enumerate() {
+            let frequency = match x {
+                0..=25 => ENGLISH_FREQUENCIES[x] * english_share * (1.0 - Self::CAPITALS),
+                26 => ENGLISH_FREQUENCIES[x] * english_share,
+                27 => letter_share * Self::CAPITALS,
+                28 => Self::DIGITS,
+                29 => Self::PUNCTUATION,
+                _ => Self::NON_PRINTABLE,
+            };
+
+            let expected = frequency * total;
+            statistic += (count - expected).powi(2) / expected;
+        }
+
+        statistic
+    }
+}
+
+impl Scorer for ChiSquared {
+    fn score(&self, input: &[u8]) -> f64 {
+        if input.is_empty() {
+            return 0.0;
+        }
+
+        1.0 / (1.0 + Self::statistic(input) / input.len() as f64)
+    }
+}
+
+/// Scores an input by the average log-likelihood of its letter
+/// n-grams (either bigrams or trigrams)
+#[derive(Copy, Clone, Debug)]
+pub struct NGrams {
+    /// The n-gram length
+    order: usize,
+    /// The likelihood assigned to an n-gram which isn't in the table
+    floor: f64,
+}
+
+impl NGrams {
+    /// Scores using English bigrams
+    pub const fn bigrams() -> Self {
+        Self {
+            order: 2,
+            floor: 0.0003,
+        }
+    }
+
+    /// Scores using English trigrams
+    pub const fn trigrams() -> Self {
+        Self {
+            order: 3,
+            floor: 0.0001,
+        }
+    }
+
+    /// Looks up the likelihood of a given (uppercase) n-gram
+    fn likelihood(&self, ngram: &[u8]) -> f64 {
+        let frequency = if self.order == 2 {
+            ENGLISH_BIGRAMS
+                .binary_search_by(|(x, _)| x[..].cmp(ngram))
+                .map(|x| ENGLISH_BIGRAMS[x].1)
+        } else {
+            ENGLISH_TRIGRAMS
+                .binary_search_by(|(x, _)| x[..].cmp(ngram))
+                .map(|x| ENGLISH_TRIGRAMS[x].1)
+        };
+
+        frequency.unwrap_or(self.floor)
+    }
+
+    /// The likelihood of the most common n-gram in the table
+    fn ceiling(&self) -> f64 {
+        if self.order == 2 {
+            ENGLISH_BIGRAMS.iter().map(|x| x.1).fold(0.0, f64::max)
+        } else {
+            ENGLISH_TRIGRAMS.iter().map(|x| x.1).fold(0.0, f64::max)
+        }
+    }
+}
+
+impl Scorer for NGrams {
+    fn score(&self, input: &[u8]) -> f64 {
+        // N-grams never span across words
+        let mut log_likelihood = 0.0;
+        let mut n_ngrams = 0;
+        for word in input.split(|x| !x.is_ascii_alphabetic()) {
+            let word = word.to_ascii_uppercase();
+            for ngram in word.windows(self.order) {
+                log_likelihood += self.likelihood(ngram).log10();
+                n_ngrams += 1;
+            }
+        }
+
+        if n_ngrams == 0 {
+            return 0.0;
+        }
+
+        // Maps the average between the floor and the most common n-gram
+        let floor = self.floor.log10();
+        let average = log_likelihood / f64::from(n_ngrams);
+        ((average - floor) / (self.ceiling().log10() - floor)).clamp(0.0, 1.0)
+    }
+}
+
+/// Scores an input by the share of its bytes which are printable
+///
+/// The share is raised to a power, so that even a few
+/// non-printable bytes are heavily penalized
+#[derive(Copy, Clone, Debug)]
+pub struct Printable {
+    /// The exponent the printable share is raised to
+    pub exponent: i32,
+}
+
+impl Default for Printable {
+    fn default() -> Self {
+        Self { exponent: 4 }
+    }
+}
+
+impl Scorer for Printable {
+    fn score(&self, input: &[u8]) -> f64 {
+        if input.is_empty() {
+            return 0.0;
+        }
+
+        let n_printable = input.iter().filter(|&&x| is_printable(x)).count();
+        (n_printable as f64 / input.len() as f64).powi(self.exponent)
+    }
+}
+
+/// Scores an input by the share of its words which are common English words
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WordList;
+
+impl Scorer for WordList {
+    fn score(&self, input: &[u8]) -> f64 {
+        let mut n_words = 0;
+        let mut n_hits = 0;
+        for word in input
+            .split(|x| !x.is_ascii_alphabetic())
+            .filter(|x| !x.is_empty())
+        {
+            let word = String::from_utf8_lossy(word).to_ascii_lowercase();
+            if ENGLISH_WORDS.binary_search(&word.as_str()).is_ok() {
+                n_hits += 1;
+            }
+            n_words += 1;
+        }
+
+        if n_words == 0 {
+            return 0.0;
+        }
+
+        f64::from(n_hits) / f64::from(n_words)
+    }
+}
+
+/// Scores a short fragment of text (e.g. one revealed by crib dragging)
+///
+/// Fragments rarely hold whole words or many n-grams, so this is the
+/// `ChiSquared` score, penalized by the share of bytes which aren't
+/// letters or spaces (raised to the fourth power)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Fragment;
+
+impl Scorer for Fragment {
+    fn score(&self, input: &[u8]) -> f64 {
+        if input.is_empty() {
+            return 0.0;
+        }
+
+        let n_letters = input
+            .iter()
+            .filter(|&&x| x.is_ascii_alphabetic() || x == b' ')
+            .count();
+        ChiSquared.score(input) * (n_letters as f64 / input.len() as f64).powi(4)
+    }
+}
+
+/// Combines several scorers
+///
+/// The score is the weighted average of every component,
+/// multiplied by every penalty
+#[derive(Default)]
+pub struct Weighted {
+    /// The weighted scorers which are averaged
+    components: Vec<(f64, Box<dyn Scorer + Send + Sync>)>,
+    /// The scorers which the average is multiplied by
+    penalties: Vec<Box<dyn Scorer + Send + Sync>>,
+}
+
+impl Weighted {
+    /// Adds a weighted component
+    pub fn component<S: Scorer + Send + Sync + 'static>(mut self, weight: f64, scorer: S) -> Self {
+        self.components.push((weight, Box::new(scorer)));
+        self
+    }
+
+    /// Adds a penalty
+    pub fn penalty<S: Scorer + Send + Sync + 'static>(mut self, scorer: S) -> Self {
+        self.penalties.push(Box::new(scorer));
+        self
+    }
+}
+
+impl Scorer for Weighted {
+    fn score(&self, input: &[u8]) -> f64 {
+        let total_weight: f64 = self.components.iter().map(|x| x.0).sum();
+        if total_weight == 0.0 {
+            return 0.0;
+        }
+
+        let average = self
+            .components
+            .iter()
+            .map(|(weight, scorer)| weight * scorer.score(input))
+            .sum::<f64>()
+            / total_weight;
+
+        self.penalties
+            .iter()
+            .fold(average, |score, penalty| score * penalty.score(input))
+    }
+}
+
+/// The default English plaintext scorer
+///
+/// This combines unigram chi-squared, bigram and trigram log-likelihood,
+/// and common word hits, penalizing non-printable bytes
+#[derive(Copy, Clone, Debug, Default)]
+pub struct English;
+
+impl English {
+    /// The weights of the chi-squared, bigram, trigram, and word list scores
+    const WEIGHTS: [f64; 4] = [0.4, 0.2, 0.1, 0.3];
+}
+
+impl Scorer for English {
+    fn score(&self, input: &[u8]) -> f64 {
+        let components = [
+            ChiSquared.score(input),
+            NGrams::bigrams().score(input),
+            NGrams::trigrams().score(input),
+            WordList.score(input),
+        ];
+
+        let average: f64 = components
+            .iter()
+            .zip(Self::WEIGHTS)
+            .map(|(score, weight)| score * weight)
+            .sum();
+
+        average * Printable::default().score(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_are_sorted() {
+        assert!(ENGLISH_BIGRAMS.windows(2).all(|x| x[0].0 < x[1].0));
+        assert!(ENGLISH_TRIGRAMS.windows(2).all(|x| x[0].0 < x[1].0));
+        assert!(ENGLISH_WORDS.windows(2).all(|x| x[0] < x[1]));
+    }
+
+    #[test]
+    fn scores_are_bounded() {
+        let inputs: [&[u8]; 4] = [b"", b"\x00\xff\x13", b"the the the", b"!!!???..."];
+
+        for input in inputs {
+            for score in [
+                ChiSquared.score(input),
+                NGrams::bigrams().score(input),
+                NGrams::trigrams().score(input),
+                Printable::default().score(input),
+                WordList.score(input),
+                Fragment.score(input),
+                English.score(input),
+            ] {
+                assert!((0.0..=1.0).contains(&score));
+            }
+        }
+    }
+
+    #[test]
+    fn lowercase_beats_capitals() {
+        let english = b"Cooking MC's like a pound of bacon";
+
+        assert!(ChiSquared.score(english) > ChiSquared.score(&english.to_ascii_uppercase()));
+    }
+
+    #[test]
+    fn english_beats_garbage() {
+        let english = b"Cooking MC's like a pound of bacon";
+        let garbage = b"\x1b77316?x\x15\x1b\x7f+x413=x9x(7-6<x7>x:9;76";
+        let punctuation = b"%-.&#!{!}&-,'!.!,{.%&!&$!,!";
+
+        for scorer in [
+            &ChiSquared as &dyn Scorer,
+            &NGrams::bigrams(),
+            &WordList,
+            &Fragment,
+            &English,
+        ] {
+            assert!(scorer.score(english) > scorer.score(garbage));
+            assert!(scorer.score(english) > scorer.score(punctuation));
+        }
+    }
+
+    #[test]
+    fn printable_penalty() {
+        assert_eq!(Printable::default().score(b"abcd"), 1.0);
+        assert_eq!(Printable { exponent: 1 }.score(b"ab\x00\xff"), 0.5);
+    }
+
+    #[test]
+    fn fragment_penalty() {
+        assert!(Fragment.score(b" supp") > Fragment.score(b"1i&1/"));
+        assert_eq!(Fragment.score(b"&&&&"), 0.0);
+    }
+
+    #[test]
+    fn weighted() {
+        let scorer = Weighted::default()
+            .component(1.0, WordList)
+            .component(3.0, Printable::default())
+            .penalty(Printable { exponent: 1 });
+
+        // Half of the words are hits, while all of the bytes are printable
+        assert_eq!(scorer.score(b"the xyz"), (0.5 + 3.0) / 4.0);
+        assert_eq!(Weighted::default().score(b"the"), 0.0);
+    }
+}
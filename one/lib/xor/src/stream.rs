@@ -0,0 +1,77 @@
+use std::iter::{Copied, Cycle};
+use std::slice::Iter;
+
+/// An iterator which XORs every byte with the next byte of a keystream
+///
+/// It ends as soon as either the input or the keystream does
+#[derive(Clone, Debug)]
+pub struct XorStream<I, K> {
+    /// The input bytes
+    input: I,
+    /// The keystream bytes
+    keystream: K,
+}
+
+impl<I: Iterator<Item = u8>, K: Iterator<Item = u8>> Iterator for XorStream<I, K> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        Some(self.input.next()? ^ self.keystream.next()?)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (input_min, input_max) = self.input.size_hint();
+        let (keystream_min, keystream_max) = self.keystream.size_hint();
+
+        let max = match (input_max, keystream_max) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        };
+        (input_min.min(keystream_min), max)
+    }
+}
+
+/// Adds XOR adapters to every byte iterator
+pub trait XorIterator: Iterator<Item = u8> + Sized {
+    /// XORs every byte with the next byte of a keystream
+    fn xor_keystream<K: IntoIterator<Item = u8>>(
+        self,
+        keystream: K,
+    ) -> XorStream<Self, K::IntoIter> {
+        XorStream {
+            input: self,
+            keystream: keystream.into_iter(),
+        }
+    }
+
+    /// XORs every byte with a repeating key
+    ///
+    /// An empty key yields no bytes
+    fn xor_repeating(self, key: &[u8]) -> XorStream<Self, Copied<Cycle<Iter<'_, u8>>>> {
+        self.xor_keystream(key.iter().cycle().copied())
+    }
+}
+
+impl<I: Iterator<Item = u8>> XorIterator for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xor::Xor;
+
+    #[test]
+    fn xor_repeating() {
+        let plaintext = b"Burning 'em, if you ain't quick and nimble";
+
+        let ciphertext: Vec<u8> = plaintext.iter().copied().xor_repeating(b"ICE").collect();
+        assert_eq!(ciphertext, Xor::repeating_key(plaintext, b"ICE").unwrap());
+        assert_eq!(b"abc".iter().copied().xor_repeating(&[]).count(), 0);
+    }
+
+    #[test]
+    fn xor_keystream() {
+        let stream = b"abcdef".iter().copied().xor_keystream([1, 2, 3]);
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+        assert_eq!(stream.collect::<Vec<u8>>(), b"```");
+    }
+}
@@ -1,79 +1,32 @@
 use crate::errors::*;
+use crate::key_length::KeyLengthEstimator;
+use crate::scorer::{English, Scorer};
 use bitvec::prelude::*;
-use std::collections::btree_map::Entry::*;
-use std::collections::BTreeMap;
 use std::io::BufRead;
 use std::io::Lines;
 
+/// A possible plaintext of a single byte XOR ciphertext
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// The key byte the ciphertext was XORed with
+    pub key: u8,
+    /// The plaintext's score (between 0 and 1)
+    pub score: f64,
+    /// The possible plaintext
+    pub plaintext: Vec<u8>,
+}
+
 pub struct Xor {}
 
 impl Xor {
     /// Calculates the probability of a string being english
-    /// by calculating its letter frequency
+    /// using the default `English` scorer
     pub fn probability(input: &str) -> f64 {
-        let mut count: BTreeMap<char, f64> = BTreeMap::new();
-        for letter in input.chars() {
-            match count.entry(letter.to_ascii_uppercase()) {
-                Vacant(entry) => {
-                    entry.insert(1f64);
-                }
-                Occupied(mut entry) => *entry.get_mut() += 1f64,
-            }
-        }
-
-        // Total number of characters in the given text
-        let total = input.len() as f64;
-
-        // Relative frequency of letters in the English language
-        let mut english_frequencies: BTreeMap<char, f64> = BTreeMap::new();
-        english_frequencies.insert('A', 0.0651738);
-        english_frequencies.insert('B', 0.0124248);
-        english_frequencies.insert('C', 0.0217339);
-        english_frequencies.insert('D', 0.0349835);
-        english_frequencies.insert('E', 0.1041442);
-        english_frequencies.insert('F', 0.0197881);
-        english_frequencies.insert('G', 0.0158610);
-        english_frequencies.insert('H', 0.0492888);
-        english_frequencies.insert('I', 0.0558094);
-        english_frequencies.insert('J', 0.0009033);
-        english_frequencies.insert('K', 0.0050529);
-        english_frequencies.insert('L', 0.0331490);
-        english_frequencies.insert('M', 0.0202124);
-        english_frequencies.insert('N', 0.0564513);
-        english_frequencies.insert('O', 0.0596302);
-        english_frequencies.insert('P', 0.0137645);
-        english_frequencies.insert('Q', 0.0008606);
-        english_frequencies.insert('R', 0.0497563);
-        english_frequencies.insert('S', 0.0515760);
-        english_frequencies.insert('T', 0.0729357);
-        english_frequencies.insert('U', 0.0225134);
-        english_frequencies.insert('V', 0.0082903);
-        english_frequencies.insert('W', 0.0171272);
-        english_frequencies.insert('X', 0.0013692);
-        english_frequencies.insert('Y', 0.0145984);
-        english_frequencies.insert('Z', 0.0007836);
-        english_frequencies.insert(' ', 0.1918182);
-
-        // Update the counts to be the relative frequency of letters in the given text
-        // and then calculate the Bhattacharyya coefficient as our score
-        let mut score = 0.0;
-        for letter in english_frequencies.keys() {
-            match count.entry(*letter) {
-                Vacant(entry) => {
-                    entry.insert(0.0);
-                }
-                Occupied(mut entry) => *entry.get_mut() /= total,
-            }
-
-            let partition_overlap = count[letter] * english_frequencies[letter];
-            score += partition_overlap.sqrt();
-        }
-
-        score
+        English.score(input.as_bytes())
     }
 
     /// XORs two vectors of equal length
-    pub fn vec(lvalue: &Vec<u8>, rvalue: &Vec<u8>) -> XorResult<Vec<u8>> {
+    pub fn vec(lvalue: &[u8], rvalue: &[u8]) -> XorResult<Vec<u8>> {
         // Checks the vector lengths
         if lvalue.len() != rvalue.len() {
             return Err(XorError::LengthNotEqual);
@@ -88,15 +41,65 @@ impl Xor {
         Ok(xor)
     }
 
+    /// XORs two slices of equal length into an output slice of the same length
+    pub fn xor_into(lvalue: &[u8], rvalue: &[u8], output: &mut [u8]) -> XorResult<()> {
+        // Checks the slice lengths
+        if lvalue.len() != rvalue.len() || lvalue.len() != output.len() {
+            return Err(XorError::LengthNotEqual);
+        }
+
+        for ((output, lvalue), rvalue) in output.iter_mut().zip(lvalue).zip(rvalue) {
+            *output = lvalue ^ rvalue;
+        }
+
+        Ok(())
+    }
+
+    /// XORs a slice of equal length into a buffer
+    pub fn xor_in_place(buffer: &mut [u8], rvalue: &[u8]) -> XorResult<()> {
+        // Checks the slice lengths
+        if buffer.len() != rvalue.len() {
+            return Err(XorError::LengthNotEqual);
+        }
+
+        for (buffer, rvalue) in buffer.iter_mut().zip(rvalue) {
+            *buffer ^= rvalue;
+        }
+
+        Ok(())
+    }
+
+    /// XORs an input with a repeating key
+    ///
+    /// The output is always as long as the input
+    pub fn repeating_key(input: &[u8], key: &[u8]) -> XorResult<Vec<u8>> {
+        let mut output = input.to_vec();
+        Self::repeating_key_in_place(&mut output, key)?;
+
+        Ok(output)
+    }
+
+    /// XORs a buffer with a repeating key
+    pub fn repeating_key_in_place(buffer: &mut [u8], key: &[u8]) -> XorResult<()> {
+        if key.is_empty() {
+            return Err(XorError::InvalidKeyLength);
+        }
+
+        for (buffer, key) in buffer.iter_mut().zip(key.iter().cycle()) {
+            *buffer ^= key;
+        }
+
+        Ok(())
+    }
+
     /// XORs two vectors of variable length
     ///
-    /// This works by padding the end of the first vector, a
-    /// more "secure" (never use XOR for any level of security)
-    /// option is `variable_vec_secure`, which padds the vectors
-    /// with random values
-    pub fn variable_vec(plaintext: &mut Vec<u8>, key: &Vec<u8>) -> Vec<u8> {
+    /// This works by padding the end of the first vector with zeroes,
+    /// which changes its length and returns a longer ciphertext
+    #[deprecated(note = "pads the plaintext, use `Xor::repeating_key` instead")]
+    pub fn variable_vec(plaintext: &mut Vec<u8>, key: &[u8]) -> Vec<u8> {
         // Pads the plaintext
-        if plaintext.len() % key.len() != 0 {
+        if !plaintext.len().is_multiple_of(key.len()) {
             plaintext.extend_from_slice(&vec![0; key.len() - plaintext.len() % key.len()][..]);
         }
 
@@ -110,6 +113,8 @@ impl Xor {
     }
 
     /// Calculates the hamming distance between two bitvecs
+    ///
+    /// When comparing whole bytes, `hamming_distance_bytes` is much faster
     pub fn hamming_distance(
         lvalue: &BitVec<u8, Lsb0>,
         rvalue: &BitVec<u8, Lsb0>,
@@ -131,32 +136,50 @@ impl Xor {
 
     /// Brutforces an XORed input (single byte)
     ///
-    /// Returns a tuple containing the probability of it being correct (english)
-    /// and the plaintext
-    pub fn single_byte_bruteforce(ciphertext: &mut Vec<u8>) -> XorResult<(u32, Vec<u8>)> {
-        let mut key = vec![0u8; ciphertext.len()];
-        let mut tree: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+    /// Returns the candidate with the highest probability of being english
+    pub fn single_byte_bruteforce(ciphertext: &[u8]) -> XorResult<Candidate> {
+        Self::single_byte_bruteforce_with(ciphertext, &English)
+    }
 
+    /// Brutforces an XORed input (single byte), rating
+    /// each possible plaintext with the given scorer
+    pub fn single_byte_bruteforce_with<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        scorer: &S,
+    ) -> XorResult<Candidate> {
+        Self::single_byte_candidates(ciphertext, 1, scorer)
+            .into_iter()
+            .next()
+            .ok_or(XorError::ProbabilityCalc)
+    }
+
+    /// Brutforces an XORed input (single byte), returning the `n` most
+    /// probable candidates according to the given scorer
+    ///
+    /// Candidates are sorted by descending score, equal
+    /// scores are sorted by ascending key
+    pub fn single_byte_candidates<S: Scorer + ?Sized>(
+        ciphertext: &[u8],
+        n: usize,
+        scorer: &S,
+    ) -> Vec<Candidate> {
         // XORs every key
-        for x in 0..u8::MAX {
-            // Generates the key
-            key.fill(x);
-
-            // XORs the input
-            let xor = Self::variable_vec(ciphertext, &key);
-            let possible_plaintext = String::from_utf8_lossy(&xor[..]).to_string();
-            let probability =
-                (Self::probability(possible_plaintext.clone().as_str()) * 100.0) as u32;
-            tree.insert(probability, xor);
-        }
+        let mut candidates: Vec<Candidate> = (0..=u8::MAX)
+            .map(|key| {
+                let plaintext: Vec<u8> = ciphertext.iter().map(|x| x ^ key).collect();
+                Candidate {
+                    key,
+                    score: scorer.score(&plaintext),
+                    plaintext,
+                }
+            })
+            .collect();
 
-        // Gets the result with the highest probability
-        let plaintext = tree
-            .into_iter()
-            .max_by_key(|p| p.clone().0)
-            .ok_or(XorError::ProbabilityCalc)?;
+        // The sort is stable, so ties keep their key order
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates.truncate(n);
 
-        Ok(plaintext)
+        candidates
     }
 
     /// Bruteforces a series of single byte XOR ciphertexts and returns
@@ -164,35 +187,40 @@ impl Xor {
     pub fn single_byte_bruteforce_list<T: BufRead>(
         ciphertexts: Lines<T>,
         hex: bool,
-    ) -> XorResult<(u32, Vec<u8>)> {
-        // Creates a BTreeMap to store the possible plaintexts
-        let mut tree: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+    ) -> XorResult<Candidate> {
+        Self::single_byte_bruteforce_list_with(ciphertexts, hex, &English)
+    }
+
+    /// Bruteforces a series of single byte XOR ciphertexts and returns the
+    /// ciphertext with the highest probability according to the given scorer
+    pub fn single_byte_bruteforce_list_with<T: BufRead, S: Scorer + ?Sized>(
+        ciphertexts: Lines<T>,
+        hex: bool,
+        scorer: &S,
+    ) -> XorResult<Candidate> {
+        let mut best: Option<Candidate> = None;
 
         // Loops through the ciphertexts
         for ciphertext in ciphertexts {
             // Bruteforces the ciphertext
-            let (probability, possible_plaintext): (u32, Vec<u8>);
-            if hex {
-                (probability, possible_plaintext) =
-                    Self::single_byte_bruteforce(&mut hex::decode(ciphertext?)?)?;
+            let candidate = if hex {
+                Self::single_byte_bruteforce_with(&hex::decode(ciphertext?)?, scorer)?
             } else {
-                (probability, possible_plaintext) =
-                    Self::single_byte_bruteforce(&mut ciphertext?.into())?;
+                Self::single_byte_bruteforce_with(ciphertext?.as_bytes(), scorer)?
+            };
+
+            // Keeps the first of any equally probable candidates
+            if best.as_ref().is_none_or(|x| candidate.score > x.score) {
+                best = Some(candidate);
             }
-            tree.insert(probability, possible_plaintext);
         }
 
-        let plaintext = tree
-            .into_iter()
-            .max_by_key(|p| p.clone().0)
-            .ok_or(XorError::ProbabilityCalc)?;
-
-        Ok(plaintext)
+        best.ok_or(XorError::ProbabilityCalc)
     }
 
     /// Divides the ciphertext into blocks of a given length
     /// The last block is not guaranteed to be the specified length
-    pub fn block_ciphertext(ciphertext: &Vec<u8>, block_length: usize) -> Vec<Vec<u8>> {
+    pub fn block_ciphertext(ciphertext: &[u8], block_length: usize) -> Vec<Vec<u8>> {
         let mut blocks: Vec<Vec<u8>> = vec![vec![]; ciphertext.len() / block_length + 1];
         for x in (0..ciphertext.len()).step_by(block_length) {
             if ciphertext.len() - x < block_length {
@@ -220,50 +248,143 @@ impl Xor {
         transposed_blocks
     }
 
-    /// Finds the most probable key length by calculating it's hamming distance
-    pub fn estimate_key_length(ciphertext: &Vec<u8>, max_length: usize) -> XorResult<usize> {
-        // Loops through the "possible" key lengths
-        let mut key_lengths: BTreeMap<u32, usize> = BTreeMap::new();
-        for possible_length in 1..max_length {
-            let sample_size = possible_length * 2;
-            let n_samples = ciphertext.len() / sample_size - 1;
-
-            // Calculates the length's score
-            let mut length_score: f64 = 0.0;
-            for x in 0..n_samples {
-                length_score += Self::hamming_distance(
-                    &BitVec::from_slice(
-                        &ciphertext[x * sample_size..x * sample_size + possible_length],
-                    ),
-                    &BitVec::from_slice(
-                        &ciphertext[x * sample_size + possible_length
-                            ..x * sample_size + possible_length * 2],
-                    ),
-                )? as f64;
-            }
-
-            // Normalizes the score
-            length_score /= possible_length as f64;
-            length_score /= n_samples as f64;
-            key_lengths.insert((length_score * 10_000.0) as u32, possible_length);
-        }
-
-        let probable_length = *key_lengths
-            .get(key_lengths.keys().next().ok_or(XorError::ProbabilityCalc)?)
-            .ok_or(XorError::ProbabilityCalc)?;
-
-        Ok(probable_length)
+    /// Finds the most probable key length (up to and including `max_length`)
+    /// by calculating the normalized hamming distance between its blocks
+    ///
+    /// Lengths which don't fit at least twice in the ciphertext are skipped
+    pub fn estimate_key_length(ciphertext: &[u8], max_length: usize) -> XorResult<usize> {
+        let ranking = Self::rank_key_lengths(ciphertext, max_length, KeyLengthEstimator::Hamming)?;
+
+        ranking
+            .first()
+            .map(|x| x.length)
+            .ok_or(XorError::InsufficientData)
     }
 
     /// Bruteforces a vector of single byte XOR ciphertexts
-    pub fn bruteforce_blocks(blocks: &mut [Vec<u8>]) -> Vec<Vec<u8>> {
+    pub fn bruteforce_blocks(blocks: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        Self::bruteforce_blocks_with(blocks, &English)
+    }
+
+    /// Bruteforces a vector of single byte XOR ciphertexts with the given scorer
+    pub fn bruteforce_blocks_with<S: Scorer + ?Sized>(
+        blocks: &[Vec<u8>],
+        scorer: &S,
+    ) -> Vec<Vec<u8>> {
         blocks
-            .iter_mut()
+            .iter()
             .map(|block| {
-                Self::single_byte_bruteforce(block)
+                Self::single_byte_bruteforce_with(block, scorer)
                     .expect("Failed to bruteforce block!")
-                    .1
+                    .plaintext
             })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_byte_bruteforce() {
+        let ciphertext =
+            hex::decode("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736")
+                .unwrap();
+
+        let candidate = Xor::single_byte_bruteforce(&ciphertext).unwrap();
+        assert_eq!(candidate.key, b'X');
+        assert_eq!(candidate.plaintext, b"Cooking MC's like a pound of bacon");
+    }
+
+    #[test]
+    fn single_byte_candidates() {
+        let ciphertext: Vec<u8> = b"the key is 0xff".iter().map(|x| x ^ 0xFF).collect();
+
+        let candidates = Xor::single_byte_candidates(&ciphertext, 5, &English);
+        assert_eq!(candidates.len(), 5);
+        assert_eq!(candidates[0].key, 0xFF);
+        assert_eq!(candidates[0].plaintext, b"the key is 0xff");
+        assert!(candidates.windows(2).all(|x| x[0].score >= x[1].score));
+
+        // Every key is tried, even when the scores are all equal
+        let candidates = Xor::single_byte_candidates(&[], usize::MAX, &English);
+        assert_eq!(candidates.len(), 256);
+        assert!(candidates
+            .iter()
+            .enumerate()
+            .all(|(x, c)| c.key as usize == x));
+    }
+
+    #[test]
+    fn estimate_key_length() {
+        let ciphertext: Vec<u8> = b"Burning 'em, if you ain't quick and nimble, I go crazy when \
+            I hear a cymbal and a hi-hat with a souped up tempo. I'm on a roll, it's time to go solo."
+            .iter()
+            .zip(b"ICE".iter().cycle())
+            .map(|(x, y)| x ^ y)
+            .collect();
+
+        // The longest key length is inclusive
+        assert_eq!(Xor::estimate_key_length(&ciphertext, 3).unwrap(), 3);
+    }
+
+    #[test]
+    fn estimate_key_length_short_ciphertexts() {
+        assert!(matches!(
+            Xor::estimate_key_length(&[], 40),
+            Err(XorError::InsufficientData)
+        ));
+        assert!(matches!(
+            Xor::estimate_key_length(b"a", 40),
+            Err(XorError::InsufficientData)
+        ));
+        assert!(matches!(
+            Xor::estimate_key_length(b"abcdef", 0),
+            Err(XorError::InvalidKeyLength)
+        ));
+
+        // Only lengths which fit twice are considered
+        assert_eq!(Xor::estimate_key_length(b"ab", 40).unwrap(), 1);
+        assert!(Xor::estimate_key_length(b"abcdefg", 40).unwrap() <= 3);
+    }
+
+    #[test]
+    fn xor_slices() {
+        let mut output = [0; 3];
+        Xor::xor_into(b"abc", &[1, 2, 3], &mut output).unwrap();
+        assert_eq!(&output, b"```");
+
+        Xor::xor_in_place(&mut output, &[1, 2, 3]).unwrap();
+        assert_eq!(&output, b"abc");
+
+        assert!(matches!(
+            Xor::xor_into(b"abc", b"ab", &mut output),
+            Err(XorError::LengthNotEqual)
+        ));
+        assert!(matches!(
+            Xor::xor_in_place(&mut output, b"abcd"),
+            Err(XorError::LengthNotEqual)
+        ));
+    }
+
+    #[test]
+    fn repeating_key() {
+        let plaintext =
+            b"Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+
+        let ciphertext = Xor::repeating_key(plaintext, b"ICE").unwrap();
+        assert_eq!(
+            hex::encode(&ciphertext),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+             a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+
+        // The length is preserved
+        assert_eq!(Xor::repeating_key(b"abcd", b"ICE").unwrap().len(), 4);
+        assert!(matches!(
+            Xor::repeating_key(b"abcd", &[]),
+            Err(XorError::InvalidKeyLength)
+        ));
+    }
+}
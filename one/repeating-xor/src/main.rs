@@ -11,7 +11,7 @@ fn main() {
         .arg(arg! ( -K --key <VALUE> "The key" ).required(true))
         .get_matches();
 
-    let mut plaintext = matches
+    let plaintext = matches
         .get_one::<String>("plaintext")
         .expect("The --plaintext option is required!")
         .as_bytes()
@@ -23,7 +23,7 @@ fn main() {
         .as_bytes()
         .to_vec();
 
-    let ciphertext = Xor::variable_vec(&mut plaintext, &key);
+    let ciphertext = Xor::repeating_key(&plaintext, &key).expect("The key can't be empty!");
 
     println!("XOR Output: {:?}", hex::encode(ciphertext));
 }
@@ -1,5 +1,9 @@
-use clap::{Arg, ArgAction, Command};
-use xor::{Xor, XorResult};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    value_parser, Arg, ArgAction, Command,
+};
+use encoding::Encoding;
+use xor::{English, LanguageModel, Scorer, Xor, XorResult};
 
 fn main() -> XorResult<()> {
     // Sets CLI options
@@ -26,9 +30,26 @@ fn main() -> XorResult<()> {
                 .short('O')
                 .long("output")
                 .require_equals(true)
-                .value_parser(["hex", "utf"])
+                .value_parser(
+                    PossibleValuesParser::new(Encoding::NAMES).try_map(|x| x.parse::<Encoding>()),
+                )
                 .help("The output format which should be returned"),
         )
+        .arg(
+            Arg::new("model")
+                .short('M')
+                .long("model")
+                .help("A language model to score plaintexts with (defaults to English)"),
+        )
+        .arg(
+            Arg::new("candidates")
+                .short('N')
+                .long("candidates")
+                .require_equals(true)
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("The number of candidates which should be displayed"),
+        )
         .get_matches();
 
     let ciphertext = matches
@@ -36,28 +57,35 @@ fn main() -> XorResult<()> {
         .expect("The --ciphertext option is required!");
     let hex = matches.get_flag("hex");
     let output = matches
-        .get_one::<String>("output")
+        .get_one::<Encoding>("output")
         .expect("The --output option is required!");
+    let n_candidates = *matches
+        .get_one::<usize>("candidates")
+        .expect("The --candidates option is required!");
+
+    let input_encoding = if hex { Encoding::Hex } else { Encoding::Raw };
+    let ciphertext = input_encoding
+        .decode(ciphertext.as_bytes())
+        .expect("Failed to decode the ciphertext!");
+
+    // Loads the language model (if one was given)
+    let scorer: Box<dyn Scorer> = match matches.get_one::<String>("model") {
+        Some(path) => Box::new(LanguageModel::load(path)?),
+        None => Box::new(English),
+    };
 
     // Bruteforces the ciphertext
-    let (probability, plaintext): (u32, Vec<u8>);
-    if hex {
-        (probability, plaintext) = Xor::single_byte_bruteforce(&mut hex::decode(ciphertext)?)?;
-    } else {
-        (probability, plaintext) =
-            Xor::single_byte_bruteforce(&mut ciphertext.as_bytes().to_vec())?;
-    }
+    let candidates = Xor::single_byte_candidates(&ciphertext, n_candidates, scorer.as_ref());
 
-    // Displays the plaintext
-    if output == "hex" {
-        println!(
-            "Output ({probability}% certainty): {:?}",
-            hex::encode(plaintext)
-        );
-    } else if output == "utf" {
+    // Displays the plaintexts
+    for candidate in candidates {
         println!(
-            "Output ({probability}% certainty): {:?}",
-            String::from_utf8_lossy(&plaintext[..])
+            "Output (key {:#04x}, {:.0}% certainty): {:?}",
+            candidate.key,
+            candidate.score * 100.0,
+            output
+                .encode_to_string(&candidate.plaintext)
+                .expect("Failed to encode output!")
         );
     }
 
@@ -0,0 +1,52 @@
+use clap::{value_parser, Arg, Command};
+use xor::{LanguageModel, XorResult};
+
+fn main() -> XorResult<()> {
+    // Sets CLI options
+    let matches = Command::new("Language Model Training")
+        .version("0.1.0")
+        .author("Federico Fusco")
+        .about("Trains a language model from a sample corpus")
+        .arg(
+            Arg::new("corpus")
+                .short('C')
+                .long("corpus")
+                .required(true)
+                .help("The path to the sample corpus"),
+        )
+        .arg(
+            Arg::new("order")
+                .short('N')
+                .long("order")
+                .require_equals(true)
+                .default_value("3")
+                .value_parser(value_parser!(usize))
+                .help("The longest n-gram the model should keep track of"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('O')
+                .long("output")
+                .required(true)
+                .help("The path the model should be saved to"),
+        )
+        .get_matches();
+
+    let corpus = matches
+        .get_one::<String>("corpus")
+        .expect("The --corpus option is required!");
+    let order = *matches
+        .get_one::<usize>("order")
+        .expect("The --order option is required!");
+    let output = matches
+        .get_one::<String>("output")
+        .expect("The --output option is required!");
+
+    // Trains and saves the model
+    let model = LanguageModel::train_file(corpus, order)?;
+    model.save(output)?;
+
+    println!("Saved an order {order} model to {output}");
+
+    Ok(())
+}